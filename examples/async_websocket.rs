@@ -0,0 +1,26 @@
+extern crate bitsors;
+use bitsors::async_websocket::AsyncBitsoWebSocket;
+use bitsors::websocket::*;
+use futures::StreamExt;
+
+#[tokio::main]
+async fn main() {
+    let mut socket = AsyncBitsoWebSocket::new().await.unwrap();
+    //subscribe to the BTC-MXN trades and diff-orders channels
+    socket
+        .subscribe(Subscription::Trades, Book::BtcMxn)
+        .await
+        .unwrap();
+    socket
+        .subscribe(Subscription::DiffOrders, Book::BtcMxn)
+        .await
+        .unwrap();
+    while let Some(response) = socket.next().await {
+        match response.unwrap() {
+            Response::Orders(r) => println!("{:?}", r),
+            Response::Trades(r) => println!("{:?}", r),
+            Response::DiffOrders(r) => println!("{:?}", r),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,22 @@
+extern crate bitsors;
+
+use bitsors::auth::BitsoCredentials;
+use bitsors::blocking::Bitso;
+
+fn main() {
+    // Set API_KEY and API_SECRET in .env file or
+    // export API_KEY="your api_key"
+    // export API_SECRET="your_api_secret"
+    let client_credential = BitsoCredentials::default().build();
+    // Or set API_KEY and API_SECRET explictly
+    // let client_credential = BitsoCredentials::default()
+    //     .api_key("this-is-my-client-id")
+    //     .api_secret("this-is-my-client-secret")
+    //     .build();
+    let bitso = Bitso::default()
+        .client_credentials_manager(client_credential)
+        .build();
+    // No async runtime needed
+    let result = bitso.get_account_balance();
+    println!("{:?}", result);
+}
@@ -1,7 +1,7 @@
 extern crate bitsors;
 
 use bitsors::auth::BitsoCredentials;
-use bitsors::client::Bitso;
+use bitsors::client::{Bitso, CancelTarget, OrderId};
 
 #[tokio::main]
 async fn main() {
@@ -17,8 +17,7 @@ async fn main() {
     let bitso = Bitso::default()
         .client_credentials_manager(client_credential)
         .build();
-    let result = bitso
-        .cancel_order(false, Some("your_oid"), None, None)
-        .await;
+    let oid = OrderId::from("your_oid");
+    let result = bitso.cancel_order(CancelTarget::Oid(&oid)).await;
     println!("{:?}", result);
 }
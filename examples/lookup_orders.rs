@@ -1,7 +1,7 @@
 extern crate bitsors;
 
 use bitsors::auth::BitsoCredentials;
-use bitsors::client::Bitso;
+use bitsors::client::{Bitso, OrderId};
 
 #[tokio::main]
 async fn main() {
@@ -17,6 +17,8 @@ async fn main() {
     let bitso = Bitso::default()
         .client_credentials_manager(client_credential)
         .build();
-    let result = bitso.get_lookup_orders(Some("your_oid"), None, None).await;
+    let result = bitso
+        .get_lookup_orders(Some(&OrderId::from("your_oid")), None, None)
+        .await;
     println!("{:?}", result);
 }
@@ -1,10 +1,10 @@
 extern crate bitsors;
 
-use bitsors::client::Bitso;
+use bitsors::client::{Bitso, Book};
 
 #[tokio::main]
 async fn main() {
     let bitso = Bitso::default().build();
-    let result = bitso.get_order_book("btc_mxn", false).await;
+    let result = bitso.get_order_book(&Book::BtcMxn, false).await;
     println!("{:?}", result);
 }
@@ -1,7 +1,7 @@
 extern crate bitsors;
 
 use bitsors::auth::BitsoCredentials;
-use bitsors::client::Bitso;
+use bitsors::client::{Bitso, OrderId};
 
 #[tokio::main]
 async fn main() {
@@ -17,6 +17,8 @@ async fn main() {
     let bitso = Bitso::default()
         .client_credentials_manager(client_credential)
         .build();
-    let result = bitso.get_order_trades(Some("your_order_trade"), None).await;
+    let result = bitso
+        .get_order_trades(Some(&OrderId::from("your_oid")), None)
+        .await;
     println!("{:?}", result);
 }
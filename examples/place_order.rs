@@ -1,7 +1,8 @@
 extern crate bitsors;
 
 use bitsors::auth::BitsoCredentials;
-use bitsors::client::{Bitso, OptionalOrderParams};
+use bitsors::client::{Bitso, Book, OptionalOrderParams, OrderSide, OrderType};
+use rust_decimal_macros::dec;
 
 #[tokio::main]
 async fn main() {
@@ -18,7 +19,7 @@ async fn main() {
         .client_credentials_manager(client_credential)
         .build();
     let optional_order_params = OptionalOrderParams {
-        major: Some("0.0001"),
+        major: Some(dec!(0.0001)),
         minor: None,
         price: None,
         stop: None,
@@ -26,7 +27,12 @@ async fn main() {
         origin_id: None,
     };
     let result = bitso
-        .place_order("btc_mxn", "sell", "market", Some(optional_order_params))
+        .place_order(
+            &Book::BtcMxn,
+            OrderSide::Sell,
+            OrderType::Market,
+            Some(optional_order_params),
+        )
         .await;
     println!("{:?}", result);
 }
@@ -1,10 +1,10 @@
 extern crate bitsors;
 
-use bitsors::client::Bitso;
+use bitsors::client::{Bitso, Book};
 
 #[tokio::main]
 async fn main() {
     let bitso = Bitso::default().build();
-    let result = bitso.get_ticker("btc_mxn").await;
+    let result = bitso.get_ticker(&Book::BtcMxn).await;
     println!("{:?}", result);
 }
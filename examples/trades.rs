@@ -1,10 +1,10 @@
 extern crate bitsors;
 
-use bitsors::client::Bitso;
+use bitsors::client::{Bitso, Book};
 
 #[tokio::main]
 async fn main() {
     let bitso = Bitso::default().build();
-    let result = bitso.get_trades("btc_mxn", None).await;
+    let result = bitso.get_trades(&Book::BtcMxn, None).await;
     println!("{:?}", result);
 }
@@ -1,7 +1,7 @@
 extern crate bitsors;
 
 use bitsors::auth::BitsoCredentials;
-use bitsors::client::Bitso;
+use bitsors::client::{Bitso, Book};
 
 #[tokio::main]
 async fn main() {
@@ -17,6 +17,6 @@ async fn main() {
     let bitso = Bitso::default()
         .client_credentials_manager(client_credential)
         .build();
-    let result = bitso.get_user_trades("btc_mxn", None, None, None).await;
+    let result = bitso.get_user_trades(&Book::BtcMxn, None, None, None).await;
     println!("{:?}", result);
 }
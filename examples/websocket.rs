@@ -1,15 +1,18 @@
 extern crate bitsors;
 use bitsors::websocket::*;
 fn main() {
-    let mut socket = BitsoWebSocket::new().unwrap();
+    // Redial and subscribe again whenever the connection drops
+    let mut socket = BitsoWebSocket::new()
+        .unwrap()
+        .reconnect_policy(ReconnectPolicy::default());
     //subscribe to the BTC-MXN orders channel
     socket
-        .subscribe(Subscription::Orders, Books::BtcMxn)
+        .subscribe(Subscription::Orders, Book::BtcMxn)
         .unwrap();
-    // You can iterate over the Books and Subscription channels
-    for book in Books::iter() {
+    // You can iterate over the known books and Subscription channels
+    for book in Book::iter() {
         for subs in Subscription::iter() {
-            socket.subscribe(subs, book).unwrap();
+            socket.subscribe(subs, book.clone()).unwrap();
         }
     }
     loop {
@@ -17,6 +20,9 @@ fn main() {
             Response::Orders(r) => println!("{:?}", r),
             Response::Trades(r) => println!("{:?}", r),
             Response::DiffOrders(r) => println!("{:?}", r),
+            Response::Ack(r) => println!("{:?}", r),
+            Response::Reconnected { .. } => println!("Reconnected"),
+            Response::Unknown(v) => println!("{}", v),
         }
     }
 }
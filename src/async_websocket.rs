@@ -0,0 +1,240 @@
+use super::error::{BitsoError, Result};
+use super::model::websocket::SubscriptionAck;
+use super::websocket::{
+    is_ack_for, parse_message, subscription_request, Book, Response, Subscription, WebSocketConfig,
+};
+use futures::{SinkExt, Stream, StreamExt};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::ErrorKind;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::{uri_mode, IntoClientRequest};
+use tokio_tungstenite::tungstenite::error::UrlError;
+use tokio_tungstenite::tungstenite::stream::Mode;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{client_async_tls_with_config, Connector, MaybeTlsStream, WebSocketStream};
+
+/// Async Bitso WebSocket object, built on Tokio.
+///
+/// It reuses the [`Book`], [`Subscription`] and [`Response`] types of the
+/// blocking [`BitsoWebSocket`](crate::websocket::BitsoWebSocket), and yields
+/// every channel message as a [`Stream`] item.
+///
+/// It is configured with the same [`WebSocketConfig`] and keeps track of its
+/// [`subscriptions`](Self::subscriptions) the same way.
+///
+/// This module is only available with the `async-websocket` feature enabled.
+///
+/// For more info see: <https://bitso.com/api_info?#websocket-api>
+///
+/// # Examples
+/// ```no_run
+/// use bitsors::async_websocket::AsyncBitsoWebSocket;
+/// use bitsors::websocket::*;
+/// use futures::StreamExt;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let mut socket = AsyncBitsoWebSocket::new().await.unwrap();
+/// socket
+///     .subscribe(Subscription::Trades, Book::BtcMxn)
+///     .await
+///     .unwrap();
+///
+/// while let Some(response) = socket.next().await {
+///     println!("{:?}", response.unwrap());
+/// }
+/// # }
+/// ```
+pub struct AsyncBitsoWebSocket {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    pending: VecDeque<Response>,
+    subscriptions: Vec<(Subscription, Book)>,
+    ack_timeout: Duration,
+}
+
+impl fmt::Debug for AsyncBitsoWebSocket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AsyncBitsoWebSocket")
+            .field("subscriptions", &self.subscriptions)
+            .field("pending", &self.pending.len())
+            .finish()
+    }
+}
+
+impl AsyncBitsoWebSocket {
+    /// Creates a new WebSocket connection.
+    pub async fn new() -> Result<Self> {
+        AsyncBitsoWebSocket::with_config(WebSocketConfig::default()).await
+    }
+
+    /// Creates a new WebSocket connection to the given URL.
+    pub async fn connect(url: &str) -> Result<Self> {
+        AsyncBitsoWebSocket::with_config(WebSocketConfig::default().url(url)).await
+    }
+
+    /// Creates a new WebSocket connection with the given settings.
+    pub async fn with_config(config: WebSocketConfig) -> Result<Self> {
+        let socket = dial(&config).await?;
+        Ok(AsyncBitsoWebSocket {
+            socket,
+            pending: VecDeque::new(),
+            subscriptions: Vec::new(),
+            ack_timeout: Duration::from_secs(10),
+        })
+    }
+
+    /// Set how long [`subscribe`](Self::subscribe) waits for Bitso's
+    /// acknowledgement before failing with [`BitsoError::AckTimeout`].
+    /// Defaults to ten seconds.
+    pub fn ack_timeout(mut self, timeout: Duration) -> Self {
+        self.ack_timeout = timeout;
+        self
+    }
+
+    /// Closes an existing WebSocket connection.
+    pub async fn close(&mut self) -> Result<()> {
+        Ok(self.socket.close(None).await?)
+    }
+
+    /// Subscribes to a given channel and waits for Bitso's acknowledgement.
+    ///
+    /// Channel messages received before the acknowledgement are kept and
+    /// yielded next by the stream. Fails with [`BitsoError::AckTimeout`] if
+    /// no acknowledgement arrives within the [ack timeout](Self::ack_timeout).
+    pub async fn subscribe(
+        &mut self,
+        subscription_type: Subscription,
+        book: Book,
+    ) -> Result<SubscriptionAck> {
+        let request = subscription_request("subscribe", subscription_type, &book);
+        self.socket.send(Message::Text(request)).await?;
+        let timeout = self.ack_timeout;
+        let wait_for_ack = async {
+            loop {
+                match self.read_response().await? {
+                    Response::Ack(mut ack)
+                        if is_ack_for(&ack, "subscribe", subscription_type, &book) =>
+                    {
+                        ack.book.get_or_insert_with(|| book.clone());
+                        let subscription = (subscription_type, book);
+                        if ack.is_ok() && !self.subscriptions.contains(&subscription) {
+                            self.subscriptions.push(subscription);
+                        }
+                        return Ok(ack);
+                    }
+                    response => self.pending.push_back(response),
+                }
+            }
+        };
+        match tokio::time::timeout(timeout, wait_for_ack).await {
+            Ok(result) => result,
+            Err(_) => Err(BitsoError::AckTimeout { timeout }),
+        }
+    }
+
+    /// Reads frames until one of them holds a [`Response`].
+    async fn read_response(&mut self) -> Result<Response> {
+        loop {
+            let message = match self.socket.next().await {
+                Some(message) => message?,
+                None => return Err(tungstenite::Error::ConnectionClosed.into()),
+            };
+            match message {
+                Message::Close(_) => return Err(tungstenite::Error::ConnectionClosed.into()),
+                Message::Ping(_) | Message::Pong(_) => continue,
+                message => {
+                    if let Some(response) = parse_message(&message.into_data())? {
+                        return Ok(response);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes a subscription to a given channel. Bitso's acknowledgement,
+    /// if any, is yielded by the stream as [`Response::Ack`].
+    pub async fn unsubscribe(&mut self, subscription_type: Subscription, book: Book) -> Result<()> {
+        let request = subscription_request("unsubscribe", subscription_type, &book);
+        self.socket.send(Message::Text(request)).await?;
+        self.subscriptions
+            .retain(|(s, b)| (*s, b) != (subscription_type, &book));
+        Ok(())
+    }
+
+    /// Channels this connection is currently subscribed to.
+    pub fn subscriptions(&self) -> &[(Subscription, Book)] {
+        &self.subscriptions
+    }
+}
+
+/// Opens a connection and does the TLS and WebSocket handshakes.
+async fn dial(config: &WebSocketConfig) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+    let mut request = config.url.as_str().into_client_request()?;
+    request.headers_mut().extend(config.headers.clone());
+    let uri = request.uri();
+    let mode = uri_mode(uri)?;
+    let host = uri
+        .host()
+        .ok_or(tungstenite::Error::Url(UrlError::NoHostName))?
+        .to_owned();
+    let port = uri.port_u16().unwrap_or(match mode {
+        Mode::Plain => 80,
+        Mode::Tls => 443,
+    });
+    let connector = match (mode, &config.tls_connector) {
+        (Mode::Tls, Some(connector)) => Some(Connector::NativeTls(connector.clone())),
+        _ => None,
+    };
+    let limits = tungstenite::protocol::WebSocketConfig {
+        max_frame_size: config.max_frame_size,
+        max_message_size: config.max_message_size,
+        ..Default::default()
+    };
+    let connect = async {
+        let stream = TcpStream::connect((host.as_str(), port))
+            .await
+            .map_err(tungstenite::Error::Io)?;
+        stream.set_nodelay(true).map_err(tungstenite::Error::Io)?;
+        let (socket, _) =
+            client_async_tls_with_config(request, stream, Some(limits), connector).await?;
+        Ok(socket)
+    };
+    match config.connect_timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, connect).await {
+            Ok(result) => result,
+            Err(_) => Err(tungstenite::Error::Io(ErrorKind::TimedOut.into()).into()),
+        },
+        None => connect.await,
+    }
+}
+
+impl Stream for AsyncBitsoWebSocket {
+    type Item = Result<Response>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(response) = self.pending.pop_front() {
+            return Poll::Ready(Some(Ok(response)));
+        }
+        loop {
+            let message = match Pin::new(&mut self.socket).poll_next(cx) {
+                Poll::Ready(Some(Ok(message))) => message,
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e.into()))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+            match message {
+                Message::Close(_) => return Poll::Ready(None),
+                Message::Ping(_) | Message::Pong(_) => continue,
+                message => match parse_message(&message.into_data()) {
+                    Ok(Some(response)) => return Poll::Ready(Some(Ok(response))),
+                    Ok(None) => continue,
+                    Err(e) => return Poll::Ready(Some(Err(e))),
+                },
+            }
+        }
+    }
+}
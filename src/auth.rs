@@ -8,9 +8,9 @@ pub struct BitsoCredentials {
     pub api_secret: String,
 }
 
-impl BitsoCredentials {
+impl Default for BitsoCredentials {
     /// Create BitsoCredentials object
-    pub fn default() -> BitsoCredentials {
+    fn default() -> BitsoCredentials {
         dotenv().ok();
         let api_key = env::var("API_KEY").unwrap_or_default();
         let api_secret = env::var("API_SECRET").unwrap_or_default();
@@ -19,7 +19,9 @@ impl BitsoCredentials {
             api_secret,
         }
     }
+}
 
+impl BitsoCredentials {
     /// Set api_key
     pub fn api_key(mut self, api_key: &str) -> BitsoCredentials {
         self.api_key = api_key.to_owned();
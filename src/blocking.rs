@@ -0,0 +1,403 @@
+//! Blocking variant of the Bitso REST client.
+//!
+//! The [`Bitso`] object in this module has the same methods and returns the
+//! same models as [`client::Bitso`](crate::client::Bitso), but every call
+//! blocks the current thread until the response arrives. It owns a small
+//! Tokio runtime, so it must not be used from within an async context.
+//!
+//! This module is only available with the `blocking` feature enabled.
+//!
+//! # Examples
+//! ```no_run
+//! use bitsors::blocking::{Bitso, Book};
+//!
+//! let bitso = Bitso::default().build();
+//! let result = bitso.get_ticker(&Book::BtcMxn);
+//! println!("{:?}", result);
+//! ```
+
+use super::auth::BitsoCredentials;
+pub use super::book::Book;
+use super::client;
+pub use super::client::{CancelTarget, OptionalOrderParams, OptionalParams};
+use super::error::Result;
+use super::http::HttpConfig;
+use super::model::private::*;
+use super::model::public::*;
+use super::model::JSONResponse;
+pub use super::model::{
+    Decimal, FundingId, OrderId, OrderSide, OrderType, OriginId, TimeInForce, TradeId, WithdrawalId,
+};
+pub use super::order::OrderRequest;
+pub use super::precision::{Precision, Rounding};
+use super::rate_limit::RateLimiter;
+use super::registry::BookRegistry;
+use super::retry::RetryPolicy;
+use super::transport::Transport;
+use reqwest::Client;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+/// Blocking Bitso API object
+#[derive(Clone)]
+pub struct Bitso {
+    inner: client::Bitso,
+    runtime: Arc<Runtime>,
+}
+
+impl Default for Bitso {
+    /// Bitso instance
+    ///
+    /// # Panics
+    /// Panics if the underlying Tokio runtime cannot be created.
+    fn default() -> Bitso {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to create the Tokio runtime of the blocking Bitso client");
+        Bitso {
+            inner: client::Bitso::default(),
+            runtime: Arc::new(runtime),
+        }
+    }
+}
+
+impl Bitso {
+    /// Set prefix
+    pub fn prefix(mut self, prefix: &str) -> Bitso {
+        self.inner = self.inner.prefix(prefix);
+        self
+    }
+
+    /// Set client credentials
+    pub fn client_credentials_manager(
+        mut self,
+        client_credential_manager: BitsoCredentials,
+    ) -> Bitso {
+        self.inner = self
+            .inner
+            .client_credentials_manager(client_credential_manager);
+        self
+    }
+
+    /// Set retry policy. By default requests are sent only once.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Bitso {
+        self.inner = self.inner.retry_policy(retry_policy);
+        self
+    }
+
+    /// Set rate limiter. Requests are not throttled by default. Pass an
+    /// `Arc<RateLimiter>` to share one budget between several Bitso objects.
+    pub fn rate_limiter<L: Into<Arc<RateLimiter>>>(mut self, rate_limiter: L) -> Bitso {
+        self.inner = self.inner.rate_limiter(rate_limiter);
+        self
+    }
+
+    /// Set the HTTP client used to send requests
+    pub fn http_client(mut self, client: Client) -> Bitso {
+        self.inner = self.inner.http_client(client);
+        self
+    }
+
+    /// Set the HTTP client settings, creating a new client for this object
+    pub fn http_config(mut self, config: HttpConfig) -> Result<Bitso> {
+        self.inner = self.inner.http_config(config)?;
+        Ok(self)
+    }
+
+    /// Set the transport used to send requests
+    pub fn transport<T: Transport + 'static>(mut self, transport: T) -> Bitso {
+        self.inner = self.inner.transport(transport);
+        self
+    }
+
+    /// Set the price and size increments of a book
+    pub fn precision(mut self, book: Book, precision: Precision) -> Bitso {
+        self.inner = self.inner.precision(book, precision);
+        self
+    }
+
+    /// Set the increments of every book listed in `registry`
+    pub fn precisions_from(mut self, registry: &BookRegistry) -> Bitso {
+        self.inner = self.inner.precisions_from(registry);
+        self
+    }
+
+    /// Build Bitso API object
+    pub fn build(self) -> Bitso {
+        self
+    }
+
+    /// The async client this object wraps
+    pub fn inner(&self) -> &client::Bitso {
+        &self.inner
+    }
+
+    /// Make a request to get available books
+    /// See: <https://bitso.com/api_info/#available-books>
+    pub fn get_available_books(&self) -> Result<JSONResponse<Vec<AvailableBook>>> {
+        self.runtime.block_on(self.inner.get_available_books())
+    }
+
+    /// Make a request to get ticker
+    /// See: <https://bitso.com/api_info/#ticker>
+    pub fn get_ticker(&self, book: &Book) -> Result<JSONResponse<BookTicker>> {
+        self.runtime.block_on(self.inner.get_ticker(book))
+    }
+
+    /// Make a request to get a specific order book
+    /// See: <https://bitso.com/api_info/#order-book>
+    pub fn get_order_book(
+        &self,
+        book: &Book,
+        aggregate: bool,
+    ) -> Result<JSONResponse<OrderBookPayload>> {
+        self.runtime
+            .block_on(self.inner.get_order_book(book, aggregate))
+    }
+
+    /// Make a request to get a specific trade
+    /// See: <https://bitso.com/api_info/#trades>
+    pub fn get_trades(
+        &self,
+        book: &Book,
+        optional_params: Option<OptionalParams<'_>>,
+    ) -> Result<JSONResponse<Vec<Trade>>> {
+        self.runtime
+            .block_on(self.inner.get_trades(book, optional_params))
+    }
+
+    /// Make a request to get account status
+    /// See: <https://bitso.com/api_info#account-status>
+    pub fn get_account_status(&self) -> Result<JSONResponse<AccountStatusPayload>> {
+        self.runtime.block_on(self.inner.get_account_status())
+    }
+
+    /// Make a request to get account balance
+    /// See: <https://bitso.com/api_info#account-balance>
+    pub fn get_account_balance(&self) -> Result<JSONResponse<Balances>> {
+        self.runtime.block_on(self.inner.get_account_balance())
+    }
+
+    /// Make a request to get fees
+    /// See: <https://bitso.com/api_info#fees>
+    pub fn get_fees(&self) -> Result<JSONResponse<FeesPayload>> {
+        self.runtime.block_on(self.inner.get_fees())
+    }
+
+    /// Make a request to get ledger
+    /// See: <https://bitso.com/api_info#ledger>
+    pub fn get_ledger(
+        &self,
+        operation_type: Option<&str>,
+        optional_params: Option<OptionalParams<'_>>,
+    ) -> Result<JSONResponse<Vec<LedgerInstance>>> {
+        self.runtime
+            .block_on(self.inner.get_ledger(operation_type, optional_params))
+    }
+
+    /// Make a request to get withdrawals
+    /// See: <https://bitso.com/api_info#withdrawals>
+    pub fn get_withdrawals(
+        &self,
+        wid: Option<&WithdrawalId>,
+        wids: Option<&[WithdrawalId]>,
+        origin_ids: Option<&[OriginId]>,
+        optional_params: Option<OptionalParams<'_>>,
+        method: Option<&str>,
+    ) -> Result<JSONResponse<Vec<WithdrawalsPayload>>> {
+        self.runtime.block_on(self.inner.get_withdrawals(
+            wid,
+            wids,
+            origin_ids,
+            optional_params,
+            method,
+        ))
+    }
+
+    /// Make a request to get fundings
+    /// See: <https://bitso.com/api_info#fundings>
+    pub fn get_fundings(
+        &self,
+        fid: Option<&FundingId>,
+        fids: Option<&[FundingId]>,
+        optional_params: Option<OptionalParams<'_>>,
+        txids: Option<Vec<&str>>,
+        method: Option<&str>,
+    ) -> Result<JSONResponse<Vec<FundingsPayload>>> {
+        self.runtime.block_on(
+            self.inner
+                .get_fundings(fid, fids, optional_params, txids, method),
+        )
+    }
+
+    /// Make a request to get user trades
+    /// See: <https://bitso.com/api_info#user-trades>
+    pub fn get_user_trades(
+        &self,
+        book: &Book,
+        tid: Option<TradeId>,
+        tids: Option<&[TradeId]>,
+        optional_params: Option<OptionalParams<'_>>,
+    ) -> Result<JSONResponse<Vec<UserTradesPayload>>> {
+        self.runtime
+            .block_on(self.inner.get_user_trades(book, tid, tids, optional_params))
+    }
+
+    /// Make a request to get order trades
+    /// See: <https://bitso.com/api_info#order-trades>
+    pub fn get_order_trades(
+        &self,
+        oid: Option<&OrderId>,
+        origin_id: Option<&OriginId>,
+    ) -> Result<JSONResponse<Vec<OrderTradesPayload>>> {
+        self.runtime
+            .block_on(self.inner.get_order_trades(oid, origin_id))
+    }
+
+    /// Make a request to get open orders
+    /// See: <https://bitso.com/api_info#open-orders>
+    pub fn get_open_orders(
+        &self,
+        book: Option<&Book>,
+        optional_params: Option<OptionalParams<'_>>,
+    ) -> Result<JSONResponse<Vec<OpenOrdersPayload>>> {
+        self.runtime
+            .block_on(self.inner.get_open_orders(book, optional_params))
+    }
+
+    /// Make a request to get lookup orders
+    /// See: <https://bitso.com/api_info#lookup-orders>
+    pub fn get_lookup_orders(
+        &self,
+        oid: Option<&OrderId>,
+        oids: Option<&[OrderId]>,
+        origin_ids: Option<&[OriginId]>,
+    ) -> Result<JSONResponse<Vec<LookupOrdersPayload>>> {
+        self.runtime
+            .block_on(self.inner.get_lookup_orders(oid, oids, origin_ids))
+    }
+
+    /// Make a request to cancel orders
+    /// See: <https://bitso.com/api_info#cancel-order>
+    pub fn cancel_order(&self, target: CancelTarget<'_>) -> Result<JSONResponse<CancelledOrders>> {
+        self.runtime.block_on(self.inner.cancel_order(target))
+    }
+
+    /// Make a post request to place an order
+    /// See: <https://bitso.com/api_info#place-an-order>
+    pub fn place_order(
+        &self,
+        book: &Book,
+        side: OrderSide,
+        r#type: OrderType,
+        optional_order_params: Option<OptionalOrderParams<'_>>,
+    ) -> Result<JSONResponse<PlaceOrderPayload>> {
+        self.runtime.block_on(
+            self.inner
+                .place_order(book, side, r#type, optional_order_params),
+        )
+    }
+
+    /// Make a post request to place an order built with [`OrderRequest`].
+    ///
+    /// The order is [checked](OrderRequest::check) before anything is sent.
+    /// See: <https://bitso.com/api_info#place-an-order>
+    pub fn place(&self, order: &OrderRequest) -> Result<JSONResponse<PlaceOrderPayload>> {
+        self.runtime.block_on(self.inner.place(order))
+    }
+
+    /// Make a request to get lookup orders
+    /// See: <https://bitso.com/api_info#lookup-orders>
+    pub fn get_funding_destination(
+        &self,
+        fund_currency: &str,
+    ) -> Result<JSONResponse<FundingDestination>> {
+        self.runtime
+            .block_on(self.inner.get_funding_destination(fund_currency))
+    }
+
+    /// Make a request to place an crypto withdrawal
+    /// See: <https://bitso.com/api_info#crypto-withdrawals>
+    pub fn crypto_withdrawal(
+        &self,
+        currency: &str,
+        amount: &str,
+        address: &str,
+        max_fee: Option<&str>,
+        destination_tag: Option<&str>,
+    ) -> Result<JSONResponse<Withdrawal<CryptoWithdrawal>>> {
+        self.runtime.block_on(self.inner.crypto_withdrawal(
+            currency,
+            amount,
+            address,
+            max_fee,
+            destination_tag,
+        ))
+    }
+
+    /// Make a request to place a speri withdrawal
+    /// See: <https://bitso.com/api_info#spei-withdrawal>
+    pub fn spei_withdrawal(
+        &self,
+        amount: &str,
+        recipient_given_names: &str,
+        recipient_family_names: &str,
+        clabe: &str,
+        notes_ref: Option<&str>,
+        numeric_ref: Option<&str>,
+    ) -> Result<JSONResponse<Withdrawal<SPEIWithdrawal>>> {
+        self.runtime.block_on(self.inner.spei_withdrawal(
+            amount,
+            recipient_given_names,
+            recipient_family_names,
+            clabe,
+            notes_ref,
+            numeric_ref,
+        ))
+    }
+
+    /// Make a request to get bank codes
+    /// See: <https://bitso.com/api_info#bank-codes>
+    pub fn get_bank_codes(&self) -> Result<JSONResponse<Vec<BankCode>>> {
+        self.runtime.block_on(self.inner.get_bank_codes())
+    }
+
+    /// Make a post request to make a debit-card ithdrawal
+    /// See: <https://bitso.com/api_info#debit-card-withdrawal>
+    pub fn debit_card_withdrawal(
+        &self,
+        amount: &str,
+        recipient_given_names: &str,
+        recipient_family_names: &str,
+        card_number: &str,
+        bank_code: &str,
+    ) -> Result<JSONResponse<Withdrawal<DebitWithdrawal>>> {
+        self.runtime.block_on(self.inner.debit_card_withdrawal(
+            amount,
+            recipient_given_names,
+            recipient_family_names,
+            card_number,
+            bank_code,
+        ))
+    }
+
+    /// Make a post request to make a phone-number withdrawal
+    /// See: <https://bitso.com/api_info#phone-number-withdrawal>
+    pub fn phone_number_withdrawal(
+        &self,
+        amount: &str,
+        recipient_given_names: &str,
+        recipient_family_names: &str,
+        phone_number: &str,
+        bank_code: &str,
+    ) -> Result<JSONResponse<Withdrawal<PhoneWithdrawal>>> {
+        self.runtime.block_on(self.inner.phone_number_withdrawal(
+            amount,
+            recipient_given_names,
+            recipient_family_names,
+            phone_number,
+            bank_code,
+        ))
+    }
+}
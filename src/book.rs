@@ -0,0 +1,190 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::Infallible;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+macro_rules! books {
+    ($($variant:ident => $name:literal,)*) => {
+        /// A Bitso order book, made of a base and a quote currency.
+        ///
+        /// Books listed by Bitso after this crate was released are kept in
+        /// [`Book::Other`], so they can still be deserialized and requested.
+        /// Two books are equal when their names are, whichever variant holds them.
+        ///
+        /// For more info, see: <https://bitso.com/api_info#available-books>
+        ///
+        /// # Examples
+        /// ```
+        /// use bitsors::book::Book;
+        ///
+        /// assert_eq!(Book::new("btc", "mxn"), Book::BtcMxn);
+        /// assert_eq!("btc_mxn".parse::<Book>().unwrap(), Book::BtcMxn);
+        /// assert_eq!(Book::BtcMxn.base(), "btc");
+        ///
+        /// let book = Book::new("sol", "mxn");
+        /// assert_eq!(book, Book::Other("sol_mxn".to_owned()));
+        /// assert_eq!(book.quote(), "mxn");
+        /// ```
+        #[derive(Debug, Clone, Eq)]
+        #[non_exhaustive]
+        pub enum Book {
+            $(
+                #[doc = $name]
+                $variant,
+            )*
+            /// A book unknown to this crate, e.g. `sol_mxn`.
+            Other(String),
+        }
+
+        impl Book {
+            /// Every book known to this crate.
+            pub const KNOWN: &'static [Book] = &[$(Book::$variant,)*];
+
+            /// Name of the book, e.g. `btc_mxn`
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Book::$variant => $name,)*
+                    Book::Other(name) => name,
+                }
+            }
+
+            fn from_name(name: String) -> Book {
+                match name.as_str() {
+                    $($name => Book::$variant,)*
+                    _ => Book::Other(name),
+                }
+            }
+        }
+    };
+}
+
+books! {
+    BtcMxn => "btc_mxn",
+    EthBtc => "eth_btc",
+    EthArs => "eth_ars",
+    EthMxn => "eth_mxn",
+    XrpBtc => "xrp_btc",
+    XrpMxn => "xrp_mxn",
+    LtcBtc => "ltc_btc",
+    LtcMxn => "ltc_mxn",
+    BchBtc => "bch_btc",
+    BchMxn => "bch_mxn",
+    TusdBtc => "tusd_btc",
+    TusdMxn => "tusd_mxn",
+    ManaBtc => "mana_btc",
+    ManaMxn => "mana_mxn",
+    BatBtc => "bat_btc",
+    BatMxn => "bat_mxn",
+    BtcArs => "btc_ars",
+    BtcDai => "btc_dai",
+    DaiMxn => "dai_mxn",
+    BtcUsd => "btc_usd",
+    XrpUsd => "xrp_usd",
+    EthUsd => "eth_usd",
+    DaiArs => "dai_ars",
+    BtcBrl => "btc_brl",
+}
+
+impl Book {
+    /// Book trading `base` against `quote`, e.g. `Book::new("btc", "mxn")`
+    pub fn new(base: &str, quote: &str) -> Book {
+        Book::from_name(format!("{}_{}", base, quote).to_lowercase())
+    }
+
+    /// Iterates over every book known to this crate
+    pub fn iter() -> impl Iterator<Item = Book> {
+        Book::KNOWN.iter().cloned()
+    }
+
+    /// Whether this crate knows about the book
+    pub fn is_known(&self) -> bool {
+        Book::KNOWN.contains(self)
+    }
+
+    /// Currency being bought or sold, e.g. `btc` in `btc_mxn`
+    pub fn base(&self) -> &str {
+        let name = self.as_str();
+        match name.find('_') {
+            Some(i) => &name[..i],
+            None => name,
+        }
+    }
+
+    /// Currency prices are expressed in, e.g. `mxn` in `btc_mxn`
+    pub fn quote(&self) -> &str {
+        let name = self.as_str();
+        match name.find('_') {
+            Some(i) => &name[i + 1..],
+            None => "",
+        }
+    }
+}
+
+impl PartialEq for Book {
+    fn eq(&self, other: &Book) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialEq<str> for Book {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Book {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Hash for Book {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Display for Book {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl AsRef<str> for Book {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl FromStr for Book {
+    type Err = Infallible;
+
+    fn from_str(name: &str) -> Result<Book, Infallible> {
+        Ok(Book::from(name))
+    }
+}
+
+impl From<&str> for Book {
+    fn from(name: &str) -> Book {
+        Book::from_name(name.to_lowercase())
+    }
+}
+
+impl From<String> for Book {
+    fn from(name: String) -> Book {
+        Book::from_name(name.to_lowercase())
+    }
+}
+
+impl Serialize for Book {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Book {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Book, D::Error> {
+        Ok(Book::from(String::deserialize(deserializer)?))
+    }
+}
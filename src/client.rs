@@ -1,8 +1,21 @@
 use super::auth::BitsoCredentials;
+pub use super::book::Book;
+pub use super::error::{ApiError, ErrorCategory, ErrorCode, ErrorDetails, RegularError};
+use super::error::{BitsoError, Result};
+use super::http::HttpConfig;
 use super::model::private::*;
 use super::model::public::*;
 use super::model::JSONResponse;
-use anyhow::Result;
+pub use super::model::{
+    Decimal, FundingId, OrderId, OrderSide, OrderType, OriginId, TimeInForce, TradeId, WithdrawalId,
+};
+pub use super::order::OrderRequest;
+use super::order::{check_known, check_precision};
+pub use super::precision::{Precision, Rounding};
+use super::rate_limit::RateLimiter;
+use super::registry::BookRegistry;
+use super::retry::{retry_after, RetryPolicy};
+use super::transport::{HttpRequest, HttpResponse, ReqwestTransport, Transport};
 use hex::encode;
 use openssl::hash::MessageDigest;
 use openssl::pkey::PKey;
@@ -10,30 +23,15 @@ use openssl::sign::Signer;
 use reqwest::header::{HeaderMap, AUTHORIZATION, CONTENT_TYPE};
 use reqwest::Client;
 use reqwest::Method;
-use reqwest::StatusCode;
 use serde::de::Deserialize;
 use serde_json::map::Map;
 use serde_json::Value;
 use std::borrow::Cow;
 use std::collections::HashMap;
-use std::fmt;
 use std::fmt::Debug;
 use std::hash::Hash;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-lazy_static! {
-    // HTTP Client
-    static ref CLIENT: Client = Client::new();
-}
-
-const EMPTY_CREDENTIALS_MSG: &str = "You need to set your Bitso API \
-                                     credentials. You can do this \
-                                     by setting environment variables \
-                                     in a `.env` file: \
-                                     API_KEY=your api_key \
-                                     API_SECRET=your_api_secret. \
-                                     For more information visit: \
-                                     `https://bitso.com/api_info#generating-api-keys`";
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 fn convert_map_to_string<
     K: Debug + Eq + Hash + ToString,
@@ -52,28 +50,21 @@ fn convert_map_to_string<
     string
 }
 
+fn join_ids<T: ToString>(ids: &[T], separator: &str) -> String {
+    ids.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
 /// API Type that indicates whether a method
 /// corresponds to the public or private API.
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ApiType {
     Public,
     Private,
 }
 
-/// API Errors associated to the Bitso API object
-#[derive(Debug, Deserialize)]
-pub enum ApiError {
-    /// A regular error is derived from
-    /// Bitso's API responses. For more information
-    /// see: <https://bitso.com/api_info#error-codes>
-    #[serde(alias = "error")]
-    RegularError {
-        success: bool,
-        code: String,
-        message: String,
-    },
-    Other(u16),
-}
-
 /// Generic optional parameters for methods
 pub struct OptionalParams<'a> {
     pub marker: Option<&'a u32>,
@@ -85,76 +76,80 @@ pub struct OptionalParams<'a> {
 ///
 /// For more info see: <https://bitso.com/api_info#place-an-order>
 pub struct OptionalOrderParams<'a> {
-    pub major: Option<&'a str>,
-    pub minor: Option<&'a str>,
-    pub price: Option<&'a str>,
-    pub stop: Option<&'a str>,
-    pub time_in_force: Option<&'a str>,
-    pub origin_id: Option<&'a str>,
-}
-
-impl fmt::Display for ApiError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            ApiError::RegularError {
-                success: _,
-                code,
-                message,
-            } => write!(f, "Bitso API error code {}: {}", code, message),
-            ApiError::Other(s) => write!(f, "Bitso API reported error code {}", s),
-        }
-    }
-}
-
-/// A regular error from Bitso's API. It holds [ErrorDetails]
-///
-/// See: <https://bitso.com/api_info#error-codes>
-#[derive(Debug, Deserialize)]
-pub struct RegularError {
-    pub success: bool,
-    pub error: ErrorDetails,
+    pub major: Option<Decimal>,
+    pub minor: Option<Decimal>,
+    pub price: Option<Decimal>,
+    pub stop: Option<Decimal>,
+    pub time_in_force: Option<TimeInForce>,
+    pub origin_id: Option<&'a OriginId>,
 }
 
-/// Details for the API calls.
+/// Orders to cancel.
 ///
-/// See: <https://bitso.com/api_info#error-codes>
-#[derive(Debug, Deserialize)]
-pub struct ErrorDetails {
-    pub code: String,
-    pub message: String,
+/// For more info see: <https://bitso.com/api_info#cancel-order>
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CancelTarget<'a> {
+    /// Every open order
+    All,
+    /// A single order
+    Oid(&'a OrderId),
+    /// Several orders by their Bitso IDs
+    Oids(&'a [OrderId]),
+    /// Several orders by the IDs given when they were placed
+    OriginIds(&'a [OriginId]),
 }
 
-impl ApiError {
-    async fn from_response(response: reqwest::Response) -> Self {
-        match response.status() {
-            StatusCode::BAD_REQUEST => {
-                let error = response.json::<RegularError>().await.unwrap();
-                ApiError::RegularError {
-                    success: error.success,
-                    code: error.error.code,
-                    message: error.error.message,
-                }
+impl CancelTarget<'_> {
+    /// Fails with [`BitsoError::Validation`] if no IDs or an empty ID is given
+    fn check(&self) -> Result<()> {
+        let (count, any_empty) = match self {
+            CancelTarget::All => return Ok(()),
+            CancelTarget::Oid(oid) => (1, oid.as_str().is_empty()),
+            CancelTarget::Oids(oids) => (oids.len(), oids.iter().any(|id| id.as_str().is_empty())),
+            CancelTarget::OriginIds(ids) => {
+                (ids.len(), ids.iter().any(|id| id.as_str().is_empty()))
             }
-            status => ApiError::Other(status.as_u16()),
+        };
+        if count == 0 {
+            return Err(BitsoError::Validation(
+                "no orders given to cancel".to_owned(),
+            ));
+        }
+        if any_empty {
+            return Err(BitsoError::Validation(
+                "empty order ID given to cancel".to_owned(),
+            ));
         }
+        Ok(())
     }
 }
 
 /// Bitso API object
+#[derive(Clone)]
 pub struct Bitso {
     pub prefix: String,
     pub client_credentials_manager: Option<BitsoCredentials>,
+    pub retry_policy: RetryPolicy,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub transport: Arc<dyn Transport>,
+    pub precisions: HashMap<Book, Precision>,
 }
 
-impl Bitso {
+impl Default for Bitso {
     /// Bitso instance
-    pub fn default() -> Bitso {
+    fn default() -> Bitso {
         Bitso {
             prefix: "https://api.bitso.com".to_owned(),
             client_credentials_manager: None,
+            retry_policy: RetryPolicy::none(),
+            rate_limiter: Arc::new(RateLimiter::unlimited()),
+            transport: Arc::new(ReqwestTransport::default()),
+            precisions: HashMap::new(),
         }
     }
+}
 
+impl Bitso {
     /// Set prefix
     pub fn prefix(mut self, prefix: &str) -> Bitso {
         self.prefix = prefix.to_owned();
@@ -170,11 +165,73 @@ impl Bitso {
         self
     }
 
+    /// Set retry policy. By default requests are sent only once.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Bitso {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Set rate limiter. Requests are not throttled by default. Pass an
+    /// `Arc<RateLimiter>` to share one budget between several Bitso objects.
+    pub fn rate_limiter<L: Into<Arc<RateLimiter>>>(mut self, rate_limiter: L) -> Bitso {
+        self.rate_limiter = rate_limiter.into();
+        self
+    }
+
+    /// Set the HTTP client used to send requests
+    pub fn http_client(mut self, client: Client) -> Bitso {
+        self.transport = Arc::new(ReqwestTransport::new(client));
+        self
+    }
+
+    /// Set the HTTP client settings, creating a new client for this object
+    pub fn http_config(mut self, config: HttpConfig) -> Result<Bitso> {
+        self.transport = Arc::new(ReqwestTransport::new(config.client()?));
+        Ok(self)
+    }
+
+    /// Set the transport used to send requests, e.g. a
+    /// [`MemoryTransport`](crate::transport::MemoryTransport) in tests
+    pub fn transport<T: Transport + 'static>(mut self, transport: T) -> Bitso {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Set the price and size increments orders for `book` are checked
+    /// against. Orders for books without one are sent unchecked.
+    pub fn precision(mut self, book: Book, precision: Precision) -> Bitso {
+        self.precisions.insert(book, precision);
+        self
+    }
+
+    /// Set the increments of every book listed in `registry`
+    pub fn precisions_from(mut self, registry: &BookRegistry) -> Bitso {
+        for limits in registry.iter() {
+            self.precisions
+                .insert(limits.book.clone(), limits.precision.clone());
+        }
+        self
+    }
+
+    /// Price and size increments orders for `book` are checked against,
+    /// if any were set
+    pub fn precision_for(&self, book: &Book) -> Option<Precision> {
+        self.precisions.get(book).cloned()
+    }
+
     /// Build Bitso API object
     pub fn build(self) -> Bitso {
         self
     }
 
+    /// Fails with [`BitsoError::MissingCredentials`] unless an API key is set
+    fn require_credentials(&self) -> Result<()> {
+        match &self.client_credentials_manager {
+            Some(c) if !c.get_key().is_empty() => Ok(()),
+            _ => Err(BitsoError::MissingCredentials),
+        }
+    }
+
     /// Construct authorization headers.
     /// See: <https://bitso.com/api_info#creating-and-signing-requests>
     pub fn auth_headers(
@@ -225,13 +282,46 @@ impl Bitso {
         payload: Option<&Value>,
         api_type: ApiType,
     ) -> Result<String> {
+        let mut attempt = 1;
+        loop {
+            let (error, retry_after) = match self.send(&method, url, payload, api_type).await {
+                Ok(response) if response.status.is_success() => return Ok(response.body),
+                Ok(response) => (
+                    BitsoError::from_response(response.status, response.body),
+                    retry_after(&response.headers),
+                ),
+                Err(e) => (e, None),
+            };
+            if !self.retry_policy.should_retry(&method, &error, attempt) {
+                return Err(error);
+            }
+            let delay = match retry_after {
+                Some(delay) => delay.min(self.retry_policy.max_delay),
+                None => self.retry_policy.backoff(attempt),
+            };
+            // Nonces have millisecond resolution, so never resend within the same millisecond.
+            tokio::time::sleep(delay.max(Duration::from_millis(1))).await;
+            attempt += 1;
+        }
+    }
+
+    /// Sends a single request. Private requests are signed
+    /// on every call so that each attempt gets a fresh nonce.
+    async fn send(
+        &self,
+        method: &Method,
+        url: &str,
+        payload: Option<&Value>,
+        api_type: ApiType,
+    ) -> Result<HttpResponse> {
+        self.rate_limiter.acquire(api_type).await;
         let mut url: Cow<str> = url.into();
 
         let mut headers = HeaderMap::new();
         if let ApiType::Private = api_type {
             headers.insert(
                 AUTHORIZATION,
-                self.auth_headers(&method, &url, payload).parse().unwrap(),
+                self.auth_headers(method, &url, payload).parse().unwrap(),
             );
             headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
         }
@@ -240,25 +330,13 @@ impl Bitso {
             url = [self.prefix.as_str(), &url].concat().into();
         }
 
-        let response = {
-            let mut builder = CLIENT.request(method, &url.into_owned());
-            if let ApiType::Private = api_type {
-                builder = builder.headers(headers);
-            }
-            if let Some(json) = payload {
-                builder = builder.json(json);
-            };
-            builder.send().await?
+        let request = HttpRequest {
+            method: method.clone(),
+            url: url.into_owned(),
+            headers,
+            body: payload.cloned(),
         };
-
-        if response.status().is_success() {
-            match response.text().await {
-                Ok(text) => Ok(text),
-                Err(e) => Err(anyhow!("Error getting text out of response {}", e)),
-            }
-        } else {
-            Err(anyhow!(ApiError::from_response(response).await))
-        }
+        self.transport.send(request).await
     }
 
     /// Makes get requests
@@ -308,14 +386,10 @@ impl Bitso {
 
     /// Function to convert result to models
     pub fn convert_result<'a, T: Deserialize<'a>>(&self, input: &'a str) -> Result<T> {
-        let result = serde_json::from_str::<T>(input).map_err(|e| {
-            format_err!(
-                "Convert result failed, reason: {:?}; content: [{:?}]",
-                e,
-                input
-            )
-        })?;
-        Ok(result)
+        serde_json::from_str::<T>(input).map_err(|e| BitsoError::Decode {
+            source: e,
+            body: input.to_owned(),
+        })
     }
 
     /// Make a request to get available books
@@ -328,7 +402,7 @@ impl Bitso {
 
     /// Make a request to get ticker
     /// See: <https://bitso.com/api_info/#ticker>
-    pub async fn get_ticker(&self, book: &str) -> Result<JSONResponse<BookTicker>> {
+    pub async fn get_ticker(&self, book: &Book) -> Result<JSONResponse<BookTicker>> {
         let mut params = HashMap::new();
         params.insert("book".to_owned(), book.to_string());
         let url = String::from("/v3/ticker/");
@@ -340,7 +414,7 @@ impl Bitso {
     /// See: <https://bitso.com/api_info/#order-book>
     pub async fn get_order_book(
         &self,
-        book: &str,
+        book: &Book,
         aggregate: bool,
     ) -> Result<JSONResponse<OrderBookPayload>> {
         let mut params = HashMap::new();
@@ -355,7 +429,7 @@ impl Bitso {
     /// See: <https://bitso.com/api_info/#trades>
     pub async fn get_trades(
         &self,
-        book: &str,
+        book: &Book,
         optional_params: Option<OptionalParams<'_>>,
     ) -> Result<JSONResponse<Vec<Trade>>> {
         let mut params = HashMap::new();
@@ -380,15 +454,7 @@ impl Bitso {
     /// See: <https://bitso.com/api_info#account-status>
     pub async fn get_account_status(&self) -> Result<JSONResponse<AccountStatusPayload>> {
         let url = String::from("/v3/account_status/");
-        let client_credentials = self.client_credentials_manager.as_ref();
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self
             .get(&url, &mut HashMap::new(), ApiType::Private)
             .await?;
@@ -399,15 +465,7 @@ impl Bitso {
     /// See: <https://bitso.com/api_info#account-balance>
     pub async fn get_account_balance(&self) -> Result<JSONResponse<Balances>> {
         let url = String::from("/v3/balance/");
-        let client_credentials = self.client_credentials_manager.as_ref();
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self
             .get(&url, &mut HashMap::new(), ApiType::Private)
             .await?;
@@ -418,15 +476,7 @@ impl Bitso {
     /// See: <https://bitso.com/api_info#fees>
     pub async fn get_fees(&self) -> Result<JSONResponse<FeesPayload>> {
         let url = String::from("/v3/fees/");
-        let client_credentials = self.client_credentials_manager.as_ref();
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self
             .get(&url, &mut HashMap::new(), ApiType::Private)
             .await?;
@@ -435,7 +485,7 @@ impl Bitso {
 
     /// Make a request to get ledger
     /// See: <https://bitso.com/api_info#ledger>
-    pub async fn get_ledger<'a>(
+    pub async fn get_ledger(
         &self,
         operation_type: Option<&str>,
         optional_params: Option<OptionalParams<'_>>,
@@ -457,40 +507,31 @@ impl Bitso {
                 params.insert("limit".to_owned(), l.to_string());
             }
         }
-        let client_credentials = self.client_credentials_manager.as_ref();
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self.get(&url, &mut params, ApiType::Private).await?;
         self.convert_result::<JSONResponse<Vec<LedgerInstance>>>(&result)
     }
 
     /// Make a request to get withdrawals
     /// See: <https://bitso.com/api_info#withdrawals>
-    pub async fn get_withdrawals<'a>(
+    pub async fn get_withdrawals(
         &self,
-        wid: Option<&str>,
-        wids: Option<Vec<&str>>,
-        origin_ids: Option<Vec<&str>>,
+        wid: Option<&WithdrawalId>,
+        wids: Option<&[WithdrawalId]>,
+        origin_ids: Option<&[OriginId]>,
         optional_params: Option<OptionalParams<'_>>,
         method: Option<&str>,
     ) -> Result<JSONResponse<Vec<WithdrawalsPayload>>> {
         let mut url = String::from("/v3/withdrawals/");
         let mut params = HashMap::new();
-        let client_credentials = self.client_credentials_manager.as_ref();
         if let Some(w) = wid {
-            url.push_str(w);
+            url.push_str(w.as_str());
             url.push('/');
         } else if let Some(ws) = wids {
-            let joined_wids = ws.join(",");
+            let joined_wids = join_ids(ws, ",");
             params.insert("wids".to_owned(), joined_wids);
         } else if let Some(oids) = origin_ids {
-            let joined_origin_ids = oids.join(",");
+            let joined_origin_ids = join_ids(oids, ",");
             params.insert("origin_ids".to_owned(), joined_origin_ids);
         }
 
@@ -509,36 +550,28 @@ impl Bitso {
         if let Some(m) = method {
             params.insert("method".to_owned(), m.to_string());
         }
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self.get(&url, &mut params, ApiType::Private).await?;
         self.convert_result::<JSONResponse<Vec<WithdrawalsPayload>>>(&result)
     }
 
     /// Make a request to get fundings
     /// See: <https://bitso.com/api_info#fundings>
-    pub async fn get_fundings<'a>(
+    pub async fn get_fundings(
         &self,
-        fid: Option<&str>,
-        fids: Option<Vec<&str>>,
+        fid: Option<&FundingId>,
+        fids: Option<&[FundingId]>,
         optional_params: Option<OptionalParams<'_>>,
         txids: Option<Vec<&str>>,
         method: Option<&str>,
     ) -> Result<JSONResponse<Vec<FundingsPayload>>> {
         let mut url = String::from("/v3/fundings/");
         let mut params = HashMap::new();
-        let client_credentials = self.client_credentials_manager.as_ref();
         if let Some(f) = fid {
-            url.push_str(f);
+            url.push_str(f.as_str());
             url.push('/');
         } else if let Some(fs) = fids {
-            let joined_fids = fs.join("-");
+            let joined_fids = join_ids(fs, "-");
             url.push_str(&joined_fids[..]);
             url.push('/');
         }
@@ -562,14 +595,7 @@ impl Bitso {
             let joined_ts = ts.join(",");
             params.insert("txids".to_owned(), joined_ts);
         }
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self.get(&url, &mut params, ApiType::Private).await?;
         self.convert_result::<JSONResponse<Vec<FundingsPayload>>>(&result)
     }
@@ -578,20 +604,19 @@ impl Bitso {
     /// See: <https://bitso.com/api_info#user-trades>
     pub async fn get_user_trades(
         &self,
-        book: &str,
-        tid: Option<&str>,
-        tids: Option<Vec<&str>>,
+        book: &Book,
+        tid: Option<TradeId>,
+        tids: Option<&[TradeId]>,
         optional_params: Option<OptionalParams<'_>>,
     ) -> Result<JSONResponse<Vec<UserTradesPayload>>> {
         let mut url = String::from("/v3/user_trades/");
         let mut params = HashMap::new();
-        let client_credentials = self.client_credentials_manager.as_ref();
         params.insert("book".to_owned(), book.to_string());
         if let Some(t) = tid {
-            url.push_str(t);
+            url.push_str(&t.to_string());
             url.push('/');
         } else if let Some(ts) = tids {
-            let joined_tids = ts.join("-");
+            let joined_tids = join_ids(ts, "-");
             url.push_str(&joined_tids[..]);
             url.push('/');
         }
@@ -608,14 +633,7 @@ impl Bitso {
                 params.insert("limit".to_owned(), l.to_string());
             }
         }
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self.get(&url, &mut params, ApiType::Private).await?;
         self.convert_result::<JSONResponse<Vec<UserTradesPayload>>>(&result)
     }
@@ -624,42 +642,33 @@ impl Bitso {
     /// See: <https://bitso.com/api_info#order-trades>
     pub async fn get_order_trades(
         &self,
-        oid: Option<&str>,
-        origin_id: Option<&str>,
+        oid: Option<&OrderId>,
+        origin_id: Option<&OriginId>,
     ) -> Result<JSONResponse<Vec<OrderTradesPayload>>> {
         let mut url = String::from("/v3/order_trades");
         let mut params = HashMap::new();
         if let Some(o) = oid {
             url.push('/');
-            url.push_str(o);
+            url.push_str(o.as_str());
             url.push('/');
         }
         if let Some(or) = origin_id {
             params.insert("origin_id".to_owned(), or.to_string());
         }
-        let client_credentials = self.client_credentials_manager.as_ref();
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self.get(&url, &mut params, ApiType::Private).await?;
         self.convert_result::<JSONResponse<Vec<OrderTradesPayload>>>(&result)
     }
 
     /// Make a request to get open orders
     /// See: <https://bitso.com/api_info#open-orders>
-    pub async fn get_open_orders<'a>(
+    pub async fn get_open_orders(
         &self,
-        book: Option<&str>,
+        book: Option<&Book>,
         optional_params: Option<OptionalParams<'_>>,
     ) -> Result<JSONResponse<Vec<OpenOrdersPayload>>> {
         let url = String::from("/v3/open_orders");
         let mut params = HashMap::new();
-        let client_credentials = self.client_credentials_manager.as_ref();
         if let Some(b) = book {
             params.insert("book".to_owned(), b.to_string());
         }
@@ -675,14 +684,7 @@ impl Bitso {
                 params.insert("limit".to_owned(), l.to_string());
             }
         }
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self.get(&url, &mut params, ApiType::Private).await?;
         self.convert_result::<JSONResponse<Vec<OpenOrdersPayload>>>(&result)
     }
@@ -691,123 +693,150 @@ impl Bitso {
     /// See: <https://bitso.com/api_info#lookup-orders>
     pub async fn get_lookup_orders(
         &self,
-        oid: Option<&str>,
-        oids: Option<Vec<&str>>,
-        origin_ids: Option<Vec<&str>>,
+        oid: Option<&OrderId>,
+        oids: Option<&[OrderId]>,
+        origin_ids: Option<&[OriginId]>,
     ) -> Result<JSONResponse<Vec<LookupOrdersPayload>>> {
         let mut url = String::from("/v3/orders/");
         let mut params = HashMap::new();
-        let client_credentials = self.client_credentials_manager.as_ref();
         if let Some(o) = oid {
-            url.push_str(o);
+            url.push_str(o.as_str());
             url.push('/');
         } else if let Some(os) = oids {
-            let joined_oids = os.join(",");
+            let joined_oids = join_ids(os, ",");
             params.insert("oids".to_owned(), joined_oids);
         } else if let Some(oids) = origin_ids {
-            let joined_origin_ids = oids.join(",");
+            let joined_origin_ids = join_ids(oids, ",");
             params.insert("origin_ids".to_owned(), joined_origin_ids);
         }
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self.get(&url, &mut params, ApiType::Private).await?;
         self.convert_result::<JSONResponse<Vec<LookupOrdersPayload>>>(&result)
     }
 
-    /// Make a request to cancel order
+    /// Make a request to cancel orders.
+    ///
+    /// Targets without IDs or with an empty ID fail with
+    /// [`BitsoError::Validation`] before anything is sent.
     /// See: <https://bitso.com/api_info#cancel-order>
     pub async fn cancel_order(
         &self,
-        all: bool,
-        oid: Option<&str>,
-        oids: Option<Vec<&str>>,
-        origin_ids: Option<Vec<&str>>,
-    ) -> Result<JSONResponse<Vec<String>>> {
+        target: CancelTarget<'_>,
+    ) -> Result<JSONResponse<CancelledOrders>> {
+        target.check()?;
         let mut url = String::from("/v3/orders/");
         let mut params = HashMap::new();
-        let client_credentials = self.client_credentials_manager.as_ref();
-        if all {
-            url.push_str("all");
-        } else if let Some(o) = oid {
-            url.push_str(o);
-            url.push('/');
-        } else if let Some(os) = oids {
-            let joined_oids = os.join(",");
-            params.insert("oids".to_owned(), joined_oids);
-        } else if let Some(oids) = origin_ids {
-            let joined_origin_ids = oids.join(",");
-            params.insert("origin_ids".to_owned(), joined_origin_ids);
-        }
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
+        let requested: Option<&[OrderId]> = match target {
+            CancelTarget::All => {
+                url.push_str("all");
+                Some(&[])
             }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
-        let result = self
-            .delete(&url, &mut HashMap::new(), ApiType::Private)
-            .await?;
-        self.convert_result::<JSONResponse<Vec<String>>>(&result)
-    }
-
-    /// Make a post request to place an order
+            CancelTarget::Oid(oid) => {
+                url.push_str(oid.as_str());
+                url.push('/');
+                Some(std::slice::from_ref(oid))
+            }
+            CancelTarget::Oids(oids) => {
+                params.insert("oids".to_owned(), join_ids(oids, ","));
+                Some(oids)
+            }
+            CancelTarget::OriginIds(origin_ids) => {
+                params.insert("origin_ids".to_owned(), join_ids(origin_ids, ","));
+                None
+            }
+        };
+        self.require_credentials()?;
+        let result = self.delete(&url, &mut params, ApiType::Private).await?;
+        let response = self.convert_result::<JSONResponse<Vec<OrderId>>>(&result)?;
+        let not_found = requested.map(|requested| {
+            requested
+                .iter()
+                .filter(|oid| !response.payload.contains(oid))
+                .cloned()
+                .collect()
+        });
+        Ok(JSONResponse {
+            success: response.success,
+            payload: CancelledOrders {
+                cancelled: response.payload,
+                not_found,
+            },
+        })
+    }
+
+    /// Make a post request to place an order.
+    ///
+    /// Sides, types and times in force unknown to this crate fail with
+    /// [`BitsoError::InvalidOrder`] before anything is sent.
     /// See: <https://bitso.com/api_info#place-an-order>
-    pub async fn place_order<'a>(
+    pub async fn place_order(
         &self,
-        book: &str,
-        side: &str,
-        r#type: &str,
+        book: &Book,
+        side: OrderSide,
+        r#type: OrderType,
         optional_order_params: Option<OptionalOrderParams<'_>>,
     ) -> Result<JSONResponse<PlaceOrderPayload>> {
+        check_known(
+            &side,
+            &r#type,
+            optional_order_params
+                .as_ref()
+                .and_then(|op| op.time_in_force.as_ref()),
+        )?;
         let url = String::from("/v3/orders/");
         // Create map for parameters
         let mut params_map = Map::new();
-        params_map.insert("book".to_owned(), Value::String(book.to_owned()));
-        params_map.insert("side".to_owned(), Value::String(side.to_owned()));
-        params_map.insert("type".to_owned(), Value::String(r#type.to_owned()));
+        params_map.insert("book".to_owned(), Value::String(book.to_string()));
+        params_map.insert("side".to_owned(), Value::String(side.to_string()));
+        params_map.insert("type".to_owned(), Value::String(r#type.to_string()));
         // Add optional params
         if let Some(op) = optional_order_params {
+            if let Some(precision) = self.precision_for(book) {
+                check_precision(&precision, op.major, op.minor, op.price, op.stop)?;
+            }
             if let Some(ma) = op.major {
-                params_map.insert("major".to_owned(), Value::String(ma.to_owned()));
+                params_map.insert("major".to_owned(), Value::String(ma.to_string()));
             }
             if let Some(mi) = op.minor {
-                params_map.insert("minor".to_owned(), Value::String(mi.to_owned()));
+                params_map.insert("minor".to_owned(), Value::String(mi.to_string()));
             }
             if let Some(p) = op.price {
-                params_map.insert("price".to_owned(), Value::String(p.to_owned()));
+                params_map.insert("price".to_owned(), Value::String(p.to_string()));
             }
             if let Some(s) = op.stop {
-                params_map.insert("stop".to_owned(), Value::String(s.to_owned()));
+                params_map.insert("stop".to_owned(), Value::String(s.to_string()));
             }
             if let Some(tif) = op.time_in_force {
-                params_map.insert("time_in_force".to_owned(), Value::String(tif.to_owned()));
+                params_map.insert("time_in_force".to_owned(), Value::String(tif.to_string()));
             }
             if let Some(oi) = op.origin_id {
-                params_map.insert("origin_id".to_owned(), Value::String(oi.to_owned()));
+                params_map.insert("origin_id".to_owned(), Value::String(oi.to_string()));
             }
         }
         let params = json!(params_map);
-        let client_credentials = self.client_credentials_manager.as_ref();
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self.post(&url, &params, ApiType::Private).await?;
         self.convert_result::<JSONResponse<PlaceOrderPayload>>(&result)
     }
 
+    /// Make a post request to place an order built with [`OrderRequest`].
+    ///
+    /// The order is [checked](OrderRequest::check) first, so malformed
+    /// orders fail with [`BitsoError::InvalidOrder`] before anything is sent.
+    /// See: <https://bitso.com/api_info#place-an-order>
+    pub async fn place(&self, order: &OrderRequest) -> Result<JSONResponse<PlaceOrderPayload>> {
+        order.check()?;
+        if let Some(precision) = self.precision_for(order.book()) {
+            order.check_precision(&precision)?;
+        }
+        let url = String::from("/v3/orders/");
+        self.require_credentials()?;
+        let result = self
+            .post(&url, &order.to_params(), ApiType::Private)
+            .await?;
+        self.convert_result::<JSONResponse<PlaceOrderPayload>>(&result)
+    }
+
     /// Make a request to get lookup orders
     /// See: <https://bitso.com/api_info#lookup-orders>
     pub async fn get_funding_destination(
@@ -817,15 +846,7 @@ impl Bitso {
         let url = String::from("/v3/funding_destination/");
         let mut params = HashMap::new();
         params.insert("fund_currency".to_owned(), fund_currency.to_string());
-        let client_credentials = self.client_credentials_manager.as_ref();
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self.get(&url, &mut params, ApiType::Private).await?;
         self.convert_result::<JSONResponse<FundingDestination>>(&result)
     }
@@ -853,15 +874,7 @@ impl Bitso {
             params_map.insert("destination_tag".to_owned(), Value::String(dt.to_owned()));
         }
         let params = json!(params_map);
-        let client_credentials = self.client_credentials_manager.as_ref();
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self.post(&url, &params, ApiType::Private).await?;
         self.convert_result::<JSONResponse<Withdrawal<CryptoWithdrawal>>>(&result)
     }
@@ -897,15 +910,7 @@ impl Bitso {
             params_map.insert("numeric_ref".to_owned(), Value::String(nur.to_owned()));
         }
         let params = json!(params_map);
-        let client_credentials = self.client_credentials_manager.as_ref();
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self.post(&url, &params, ApiType::Private).await?;
         self.convert_result::<JSONResponse<Withdrawal<SPEIWithdrawal>>>(&result)
     }
@@ -914,15 +919,7 @@ impl Bitso {
     /// See: <https://bitso.com/api_info#bank-codes>
     pub async fn get_bank_codes(&self) -> Result<JSONResponse<Vec<BankCode>>> {
         let url = String::from("/v3/mx_bank_codes/");
-        let client_credentials = self.client_credentials_manager.as_ref();
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self
             .get(&url, &mut HashMap::new(), ApiType::Private)
             .await?;
@@ -947,15 +944,7 @@ impl Bitso {
             "card_number": card_number,
             "bank_code": bank_code
         });
-        let client_credentials = self.client_credentials_manager.as_ref();
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self.post(&url, &params, ApiType::Private).await?;
         self.convert_result::<JSONResponse<Withdrawal<DebitWithdrawal>>>(&result)
     }
@@ -978,15 +967,7 @@ impl Bitso {
             "phone_number": phone_number,
             "bank_code": bank_code
         });
-        let client_credentials = self.client_credentials_manager.as_ref();
-        match client_credentials {
-            Some(c) => {
-                if c.get_key().is_empty() {
-                    return Err(anyhow!(EMPTY_CREDENTIALS_MSG));
-                }
-            }
-            None => return Err(anyhow!(EMPTY_CREDENTIALS_MSG)),
-        }
+        self.require_credentials()?;
         let result = self.post(&url, &params, ApiType::Private).await?;
         self.convert_result::<JSONResponse<Withdrawal<PhoneWithdrawal>>>(&result)
     }
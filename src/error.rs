@@ -0,0 +1,465 @@
+use super::book::Book;
+use super::model::Decimal;
+use reqwest::StatusCode;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Result type returned by every REST and WebSocket call in this crate.
+pub type Result<T> = std::result::Result<T, BitsoError>;
+
+/// Error message returned when the private API is called without credentials.
+const EMPTY_CREDENTIALS_MSG: &str = "You need to set your Bitso API \
+                                     credentials. You can do this \
+                                     by setting environment variables \
+                                     in a `.env` file: \
+                                     API_KEY=your api_key \
+                                     API_SECRET=your_api_secret. \
+                                     For more information visit: \
+                                     `https://bitso.com/api_info#generating-api-keys`";
+
+/// Errors returned by the Bitso REST and WebSocket clients.
+#[derive(Debug)]
+pub enum BitsoError {
+    /// The request could not be built, sent, or its response could not be read.
+    Transport(reqwest::Error),
+    /// Bitso answered with a non-success status and a body that is not a Bitso error.
+    Http { status: StatusCode, body: String },
+    /// Bitso answered with one of its documented error codes.
+    ///
+    /// See: <https://bitso.com/api_info#error-codes>
+    Api { status: StatusCode, error: ApiError },
+    /// The response body could not be decoded into the expected model.
+    Decode {
+        source: serde_json::Error,
+        body: String,
+    },
+    /// A private endpoint was called without an API key and secret.
+    MissingCredentials,
+    /// The request was rejected locally before being sent.
+    Validation(String),
+    /// An order was rejected locally before being sent.
+    InvalidOrder(OrderError),
+    /// The WebSocket connection failed.
+    WebSocket(Box<tungstenite::Error>),
+    /// The WebSocket received neither keep-alive nor channel messages
+    /// for longer than its stale timeout.
+    Stale { since: Duration },
+    /// Bitso did not acknowledge a subscription within the given time.
+    AckTimeout { timeout: Duration },
+    /// Reading or writing a WebSocket recording failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for BitsoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BitsoError::Transport(e) => write!(f, "{}", e),
+            BitsoError::Http { status, body } => {
+                write!(f, "Bitso API returned HTTP status {}: {}", status, body)
+            }
+            BitsoError::Api { error, .. } => write!(f, "{}", error),
+            BitsoError::Decode { source, body } => write!(
+                f,
+                "Convert result failed, reason: {}; content: [{}]",
+                source, body
+            ),
+            BitsoError::MissingCredentials => write!(f, "{}", EMPTY_CREDENTIALS_MSG),
+            BitsoError::Validation(msg) => write!(f, "Invalid request: {}", msg),
+            BitsoError::InvalidOrder(e) => write!(f, "Invalid order: {}", e),
+            BitsoError::WebSocket(e) => write!(f, "{}", e),
+            BitsoError::Stale { since } => {
+                write!(f, "No WebSocket message received for {:?}", since)
+            }
+            BitsoError::AckTimeout { timeout } => {
+                write!(f, "Subscription not acknowledged within {:?}", timeout)
+            }
+            BitsoError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for BitsoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BitsoError::Transport(e) => Some(e),
+            BitsoError::Decode { source, .. } => Some(source),
+            BitsoError::WebSocket(e) => Some(e.as_ref()),
+            BitsoError::Io(e) => Some(e),
+            BitsoError::InvalidOrder(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for BitsoError {
+    fn from(error: reqwest::Error) -> Self {
+        BitsoError::Transport(error)
+    }
+}
+
+impl From<io::Error> for BitsoError {
+    fn from(error: io::Error) -> Self {
+        BitsoError::Io(error)
+    }
+}
+
+impl From<tungstenite::Error> for BitsoError {
+    fn from(error: tungstenite::Error) -> Self {
+        BitsoError::WebSocket(Box::new(error))
+    }
+}
+
+impl From<OrderError> for BitsoError {
+    fn from(error: OrderError) -> Self {
+        BitsoError::InvalidOrder(error)
+    }
+}
+
+/// Reasons an [`OrderRequest`](crate::order::OrderRequest) is rejected
+/// before it is sent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderError {
+    /// Neither `major` nor `minor` was set.
+    MissingSize,
+    /// Both `major` and `minor` were set; Bitso accepts only one.
+    MajorAndMinor,
+    /// The side, type or time in force is unknown to this crate.
+    Unknown { field: &'static str, value: String },
+    /// A size or price is zero or negative.
+    NotPositive { field: &'static str, value: Decimal },
+    /// Time in force was set on a market order; it only applies to limit orders.
+    TimeInForceOnMarketOrder,
+    /// The order was checked against the limits of another book.
+    WrongBook { order: Book, limits: Book },
+    /// A price or size has more decimal places than the book accepts.
+    TooPrecise {
+        field: &'static str,
+        value: Decimal,
+        tick: Decimal,
+    },
+    /// The amount, price or value of the order falls outside the book limits.
+    OutOfRange {
+        field: &'static str,
+        value: Decimal,
+        minimum: Option<Decimal>,
+        maximum: Option<Decimal>,
+    },
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderError::MissingSize => write!(f, "either major or minor must be set"),
+            OrderError::MajorAndMinor => write!(f, "major and minor cannot both be set"),
+            OrderError::Unknown { field, value } => write!(f, "unknown {} {:?}", field, value),
+            OrderError::NotPositive { field, value } => {
+                write!(f, "{} must be positive, got {}", field, value)
+            }
+            OrderError::TimeInForceOnMarketOrder => {
+                write!(f, "time in force only applies to limit orders")
+            }
+            OrderError::WrongBook { order, limits } => write!(
+                f,
+                "order for book {} checked against the limits of {}",
+                order, limits
+            ),
+            OrderError::TooPrecise { field, value, tick } => write!(
+                f,
+                "{} {} is not a multiple of the book tick {}",
+                field, value, tick
+            ),
+            OrderError::OutOfRange {
+                field,
+                value,
+                minimum,
+                maximum,
+            } => {
+                write!(f, "{} {} is outside the book limits", field, value)?;
+                match (minimum, maximum) {
+                    (Some(min), Some(max)) => write!(f, " [{}, {}]", min, max),
+                    (Some(min), None) => write!(f, " [{}, ..)", min),
+                    (None, Some(max)) => write!(f, " (.., {}]", max),
+                    (None, None) => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl Error for OrderError {}
+
+/// API Errors associated to the Bitso API object
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum ApiError {
+    /// A regular error is derived from
+    /// Bitso's API responses. For more information
+    /// see: <https://bitso.com/api_info#error-codes>
+    #[serde(alias = "error")]
+    RegularError {
+        success: bool,
+        code: ErrorCode,
+        message: String,
+    },
+    /// An error status without a Bitso error body. The clients report
+    /// these as [`BitsoError::Http`], which also keeps the body.
+    Other(u16),
+}
+
+impl ApiError {
+    /// The Bitso error code carried by this error, if any.
+    pub fn code(&self) -> Option<&ErrorCode> {
+        match self {
+            ApiError::RegularError { code, .. } => Some(code),
+            ApiError::Other(_) => None,
+        }
+    }
+}
+
+/// Broad groups of Bitso error codes, useful to decide how to react to a failure.
+///
+/// See: <https://bitso.com/api_info#error-codes>
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// Unknown or internal errors on Bitso's side.
+    General,
+    /// Malformed requests, such as invalid payloads or HTTP methods.
+    Request,
+    /// Missing, disabled or incorrect API credentials.
+    Authentication,
+    /// Too many requests in the current window.
+    RateLimit,
+    /// Not enough balance to carry out the operation.
+    InsufficientFunds,
+    /// Invalid order parameters, such as unknown books or amounts.
+    InvalidOrderParams,
+    /// The order book is disabled for trading.
+    BookDisabled,
+    /// Errors specific to withdrawals.
+    Withdrawal,
+    /// A code this crate does not know about.
+    Unknown,
+}
+
+/// Documented Bitso error codes.
+///
+/// Codes that are not part of this catalogue are kept in [`ErrorCode::Other`],
+/// so newly introduced codes never break deserialization.
+///
+/// See: <https://bitso.com/api_info#error-codes>
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum ErrorCode {
+    /// 0101: Unknown error
+    UnknownError,
+    /// 0102: Invalid JSON payload
+    InvalidJsonPayload,
+    /// 0201: Incorrect API key or key does not exist
+    IncorrectApiKey,
+    /// 0202: Key is not enabled
+    KeyNotEnabled,
+    /// 0203: Invalid nonce
+    InvalidNonce,
+    /// 0204: Incorrect signature
+    IncorrectSignature,
+    /// 0301: Unknown order book
+    UnknownOrderBook,
+    /// 0302: Incorrect time-in-force value
+    IncorrectTimeInForce,
+    /// 0303: Invalid duration value
+    InvalidDuration,
+    /// 0304: A field of the request is invalid
+    InvalidField,
+    /// 0343: The order book is disabled
+    BookDisabled,
+    /// 0379: Insufficient funds
+    InsufficientFunds,
+    /// 0401: Incorrect HTTP method
+    IncorrectHttpMethod,
+    /// 0403: Too many requests
+    TooManyRequests,
+    /// 0601: Withdrawal method is disabled
+    WithdrawalMethodDisabled,
+    /// 0602: Withdrawal limit exceeded
+    WithdrawalLimitExceeded,
+    /// Any code not listed above.
+    Other(String),
+}
+
+impl ErrorCode {
+    /// The code as sent by Bitso, e.g. `"0301"`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ErrorCode::UnknownError => "0101",
+            ErrorCode::InvalidJsonPayload => "0102",
+            ErrorCode::IncorrectApiKey => "0201",
+            ErrorCode::KeyNotEnabled => "0202",
+            ErrorCode::InvalidNonce => "0203",
+            ErrorCode::IncorrectSignature => "0204",
+            ErrorCode::UnknownOrderBook => "0301",
+            ErrorCode::IncorrectTimeInForce => "0302",
+            ErrorCode::InvalidDuration => "0303",
+            ErrorCode::InvalidField => "0304",
+            ErrorCode::BookDisabled => "0343",
+            ErrorCode::InsufficientFunds => "0379",
+            ErrorCode::IncorrectHttpMethod => "0401",
+            ErrorCode::TooManyRequests => "0403",
+            ErrorCode::WithdrawalMethodDisabled => "0601",
+            ErrorCode::WithdrawalLimitExceeded => "0602",
+            ErrorCode::Other(code) => code,
+        }
+    }
+
+    /// The category this code belongs to. Unknown codes are grouped
+    /// by their two leading digits, as Bitso does in its documentation.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ErrorCode::UnknownError => ErrorCategory::General,
+            ErrorCode::InvalidJsonPayload | ErrorCode::IncorrectHttpMethod => {
+                ErrorCategory::Request
+            }
+            ErrorCode::IncorrectApiKey
+            | ErrorCode::KeyNotEnabled
+            | ErrorCode::InvalidNonce
+            | ErrorCode::IncorrectSignature => ErrorCategory::Authentication,
+            ErrorCode::UnknownOrderBook
+            | ErrorCode::IncorrectTimeInForce
+            | ErrorCode::InvalidDuration
+            | ErrorCode::InvalidField => ErrorCategory::InvalidOrderParams,
+            ErrorCode::BookDisabled => ErrorCategory::BookDisabled,
+            ErrorCode::InsufficientFunds => ErrorCategory::InsufficientFunds,
+            ErrorCode::TooManyRequests => ErrorCategory::RateLimit,
+            ErrorCode::WithdrawalMethodDisabled | ErrorCode::WithdrawalLimitExceeded => {
+                ErrorCategory::Withdrawal
+            }
+            ErrorCode::Other(code) => match code.get(..2) {
+                Some("01") => ErrorCategory::General,
+                Some("02") => ErrorCategory::Authentication,
+                Some("03") => ErrorCategory::InvalidOrderParams,
+                Some("04") => ErrorCategory::Request,
+                Some("06") => ErrorCategory::Withdrawal,
+                _ => ErrorCategory::Unknown,
+            },
+        }
+    }
+
+    /// Whether the same request may succeed if sent again.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ErrorCode::UnknownError | ErrorCode::InvalidNonce | ErrorCode::TooManyRequests
+        )
+    }
+}
+
+impl FromStr for ErrorCode {
+    type Err = std::convert::Infallible;
+
+    fn from_str(code: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(match code {
+            "0101" => ErrorCode::UnknownError,
+            "0102" => ErrorCode::InvalidJsonPayload,
+            "0201" => ErrorCode::IncorrectApiKey,
+            "0202" => ErrorCode::KeyNotEnabled,
+            "0203" => ErrorCode::InvalidNonce,
+            "0204" => ErrorCode::IncorrectSignature,
+            "0301" => ErrorCode::UnknownOrderBook,
+            "0302" => ErrorCode::IncorrectTimeInForce,
+            "0303" => ErrorCode::InvalidDuration,
+            "0304" => ErrorCode::InvalidField,
+            "0343" => ErrorCode::BookDisabled,
+            "0379" => ErrorCode::InsufficientFunds,
+            "0401" => ErrorCode::IncorrectHttpMethod,
+            "0403" => ErrorCode::TooManyRequests,
+            "0601" => ErrorCode::WithdrawalMethodDisabled,
+            "0602" => ErrorCode::WithdrawalLimitExceeded,
+            other => ErrorCode::Other(other.to_owned()),
+        })
+    }
+}
+
+impl From<String> for ErrorCode {
+    fn from(code: String) -> Self {
+        match ErrorCode::from_str(&code) {
+            Ok(ErrorCode::Other(_)) | Err(_) => ErrorCode::Other(code),
+            Ok(known) => known,
+        }
+    }
+}
+
+impl From<ErrorCode> for String {
+    fn from(code: ErrorCode) -> Self {
+        code.as_str().to_owned()
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ApiError::RegularError {
+                success: _,
+                code,
+                message,
+            } => write!(f, "Bitso API error code {}: {}", code, message),
+            ApiError::Other(s) => write!(f, "Bitso API reported error code {}", s),
+        }
+    }
+}
+
+/// A regular error from Bitso's API. It holds [ErrorDetails]
+///
+/// See: <https://bitso.com/api_info#error-codes>
+#[derive(Debug, Deserialize)]
+pub struct RegularError {
+    pub success: bool,
+    pub error: ErrorDetails,
+}
+
+/// Details for the API calls.
+///
+/// See: <https://bitso.com/api_info#error-codes>
+#[derive(Debug, Deserialize)]
+pub struct ErrorDetails {
+    pub code: String,
+    pub message: String,
+}
+
+impl BitsoError {
+    /// The Bitso error code, if Bitso reported one.
+    pub fn code(&self) -> Option<&ErrorCode> {
+        match self {
+            BitsoError::Api { error, .. } => error.code(),
+            _ => None,
+        }
+    }
+
+    /// Builds an error out of a non-success response, keeping the
+    /// raw body whenever it is not a Bitso error object.
+    pub(crate) fn from_response(status: StatusCode, body: String) -> Self {
+        match serde_json::from_str::<RegularError>(&body) {
+            Ok(error) => BitsoError::Api {
+                status,
+                error: ApiError::RegularError {
+                    success: error.success,
+                    code: ErrorCode::from(error.error.code),
+                    message: error.error.message,
+                },
+            },
+            Err(_) => BitsoError::Http { status, body },
+        }
+    }
+}
+
+/// Unwraps a field Bitso is expected to always send, reporting a
+/// validation error naming it otherwise.
+pub(crate) fn required(field: &str, value: Option<Decimal>) -> Result<Decimal> {
+    value.ok_or_else(|| BitsoError::Validation(format!("missing {}", field)))
+}
@@ -0,0 +1,102 @@
+use super::error::Result;
+use reqwest::header::HeaderMap;
+use reqwest::{Certificate, Client, Proxy};
+use std::time::Duration;
+
+/// Settings for the HTTP client owned by a [`Bitso`](crate::client::Bitso) object.
+///
+/// # Examples
+/// ```
+/// use bitsors::client::Bitso;
+/// use bitsors::http::HttpConfig;
+/// use std::time::Duration;
+///
+/// let config = HttpConfig::default()
+///     .timeout(Duration::from_secs(10))
+///     .user_agent("my-trading-bot/1.0")
+///     .build();
+/// let bitso = Bitso::default().http_config(config).unwrap().build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HttpConfig {
+    pub timeout: Option<Duration>,
+    pub connect_timeout: Option<Duration>,
+    pub proxy: Option<Proxy>,
+    pub user_agent: Option<String>,
+    pub default_headers: HeaderMap,
+    pub pool_max_idle_per_host: Option<usize>,
+    pub root_certificates: Vec<Certificate>,
+}
+
+impl HttpConfig {
+    /// Set the total timeout of a request
+    pub fn timeout(mut self, timeout: Duration) -> HttpConfig {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for establishing a connection
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> HttpConfig {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Set the proxy every request is sent through
+    pub fn proxy(mut self, proxy: Proxy) -> HttpConfig {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Set the `User-Agent` header
+    pub fn user_agent(mut self, user_agent: &str) -> HttpConfig {
+        self.user_agent = Some(user_agent.to_owned());
+        self
+    }
+
+    /// Set headers added to every request
+    pub fn default_headers(mut self, default_headers: HeaderMap) -> HttpConfig {
+        self.default_headers = default_headers;
+        self
+    }
+
+    /// Set the maximum number of idle connections kept per host
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> HttpConfig {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Add a trusted root certificate
+    pub fn root_certificate(mut self, certificate: Certificate) -> HttpConfig {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Build HttpConfig object
+    pub fn build(self) -> HttpConfig {
+        self
+    }
+
+    /// Creates a new HTTP client out of these settings.
+    pub fn client(&self) -> Result<Client> {
+        let mut builder = Client::builder().default_headers(self.default_headers.clone());
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        for certificate in &self.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+        Ok(builder.build()?)
+    }
+}
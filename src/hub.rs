@@ -0,0 +1,304 @@
+use super::error::{BitsoError, Result};
+use super::websocket::{BitsoWebSocket, Book, Response, ResponseSource, Subscription};
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// What the hub does when a consumer's buffer is full.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Backpressure {
+    /// Drop the oldest buffered message to make room for the new one.
+    DropOldest,
+    /// Wait until the consumer makes room. This stalls every other consumer.
+    Block,
+    /// Stop delivering to the consumer. Messages already buffered can
+    /// still be received.
+    Disconnect,
+}
+
+#[derive(Debug)]
+struct Queue {
+    buffer: VecDeque<Response>,
+    capacity: usize,
+    backpressure: Backpressure,
+    dropped: u64,
+    /// Set by the hub, either because it stopped or because the consumer lagged.
+    closed: bool,
+    lagged: bool,
+    /// Set when the receiver is dropped.
+    detached: bool,
+}
+
+#[derive(Debug)]
+struct Channel {
+    queue: Mutex<Queue>,
+    changed: Condvar,
+}
+
+impl Channel {
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Hands `response` to the consumer, following its backpressure policy.
+    fn push(&self, response: Response) {
+        let mut queue = self.lock();
+        if queue.closed || queue.detached {
+            return;
+        }
+        while queue.buffer.len() >= queue.capacity {
+            match queue.backpressure {
+                Backpressure::DropOldest => {
+                    queue.buffer.pop_front();
+                    queue.dropped += 1;
+                }
+                Backpressure::Block => {
+                    queue = self.changed.wait(queue).unwrap_or_else(|e| e.into_inner());
+                    if queue.detached {
+                        return;
+                    }
+                }
+                Backpressure::Disconnect => {
+                    queue.closed = true;
+                    queue.lagged = true;
+                    queue.dropped += 1;
+                    self.changed.notify_all();
+                    return;
+                }
+            }
+        }
+        queue.buffer.push_back(response);
+        self.changed.notify_all();
+    }
+
+    fn close(&self) {
+        self.lock().closed = true;
+        self.changed.notify_all();
+    }
+
+    fn is_detached(&self) -> bool {
+        self.lock().detached
+    }
+}
+
+#[derive(Debug)]
+struct Consumer {
+    subscription: Subscription,
+    book: Book,
+    channel: Arc<Channel>,
+}
+
+impl Consumer {
+    fn wants(&self, response: &Response) -> bool {
+        match response {
+            Response::Trades(r) => self.subscription == Subscription::Trades && r.book == self.book,
+            Response::DiffOrders(r) => {
+                self.subscription == Subscription::DiffOrders && r.book == self.book
+            }
+            Response::Orders(r) => self.subscription == Subscription::Orders && r.book == self.book,
+            // Every consumer must know that messages may have been missed
+            Response::Reconnected { .. } => true,
+            Response::Ack(_) | Response::Unknown(_) => false,
+        }
+    }
+}
+
+/// Shares one [`BitsoWebSocket`] connection, or any other
+/// [`ResponseSource`], between several consumers.
+///
+/// Each consumer gets a [`HubReceiver`] with the messages of a single book
+/// and channel, plus every [`Response::Reconnected`] event. Channels
+/// requested by several consumers are only subscribed to once.
+///
+/// Consumers must be added with [`subscribe`](Self::subscribe) before the
+/// hub starts reading: [`run`](Self::run) takes the hub, so no consumer can
+/// be added afterwards.
+///
+/// # Examples
+/// ```no_run
+/// use bitsors::hub::{Backpressure, WebSocketHub};
+/// use bitsors::websocket::*;
+/// use std::thread;
+///
+/// let mut hub = WebSocketHub::new(BitsoWebSocket::new().unwrap());
+/// let trades = hub
+///     .subscribe(Subscription::Trades, Book::BtcMxn, 1024, Backpressure::Block)
+///     .unwrap();
+/// let ticker = hub
+///     .subscribe(Subscription::Trades, Book::BtcMxn, 1, Backpressure::DropOldest)
+///     .unwrap();
+/// let hub = hub.run();
+///
+/// thread::spawn(move || {
+///     while let Some(response) = ticker.recv() {
+///         println!("Last trade: {:?}", response);
+///     }
+/// });
+/// while let Some(response) = trades.recv() {
+///     println!("{:?}", response);
+/// }
+/// println!("Hub stopped: {:?}", hub.join().unwrap());
+/// ```
+#[derive(Debug)]
+pub struct WebSocketHub<S = BitsoWebSocket> {
+    socket: S,
+    subscriptions: Vec<(Subscription, Book)>,
+    consumers: Vec<Consumer>,
+}
+
+impl<S: ResponseSource + Send + 'static> WebSocketHub<S> {
+    /// Hub reading from the given connection
+    pub fn new(socket: S) -> WebSocketHub<S> {
+        WebSocketHub {
+            socket,
+            subscriptions: Vec::new(),
+            consumers: Vec::new(),
+        }
+    }
+
+    /// Adds a consumer of the given book and channel, subscribing to it
+    /// unless another consumer already did.
+    ///
+    /// `capacity` is the number of messages buffered for the consumer, and
+    /// `backpressure` what happens once they are all waiting to be received.
+    pub fn subscribe(
+        &mut self,
+        subscription_type: Subscription,
+        book: Book,
+        capacity: usize,
+        backpressure: Backpressure,
+    ) -> Result<HubReceiver> {
+        let subscription = (subscription_type, book.clone());
+        if !self.subscriptions.contains(&subscription) {
+            let ack = self.socket.subscribe(subscription_type, book.clone())?;
+            if !ack.is_ok() {
+                return Err(BitsoError::Validation(format!(
+                    "Bitso rejected the subscription to {} {}: {}",
+                    subscription_type, book, ack.response
+                )));
+            }
+            self.subscriptions.push(subscription);
+        }
+        let channel = Arc::new(Channel {
+            queue: Mutex::new(Queue {
+                buffer: VecDeque::new(),
+                capacity: capacity.max(1),
+                backpressure,
+                dropped: 0,
+                closed: false,
+                lagged: false,
+                detached: false,
+            }),
+            changed: Condvar::new(),
+        });
+        self.consumers.push(Consumer {
+            subscription: subscription_type,
+            book,
+            channel: Arc::clone(&channel),
+        });
+        Ok(HubReceiver { channel })
+    }
+
+    /// Starts dispatching messages on a new thread.
+    ///
+    /// The thread stops when the connection fails or every receiver has been
+    /// dropped, and returns the error that stopped it, if any. Errors the
+    /// source [recovers from](ResponseSource::is_recoverable), such as
+    /// [`BitsoError::Stale`], are skipped. Receivers are closed once it stops.
+    pub fn run(mut self) -> JoinHandle<Result<()>> {
+        thread::spawn(move || {
+            let result = self.dispatch();
+            for consumer in &self.consumers {
+                consumer.channel.close();
+            }
+            if result.is_ok() {
+                self.socket.close().ok();
+            }
+            result
+        })
+    }
+
+    fn dispatch(&mut self) -> Result<()> {
+        loop {
+            self.consumers
+                .retain(|consumer| !consumer.channel.is_detached());
+            if self.consumers.is_empty() {
+                return Ok(());
+            }
+            let response = match self.socket.read() {
+                Ok(response) => response,
+                Err(error) if self.socket.is_recoverable(&error) => continue,
+                Err(error) => return Err(error),
+            };
+            for consumer in self.consumers.iter().filter(|c| c.wants(&response)) {
+                consumer.channel.push(response.clone());
+            }
+        }
+    }
+}
+
+/// Messages of one book and channel, handed out by a [`WebSocketHub`].
+#[derive(Debug)]
+pub struct HubReceiver {
+    channel: Arc<Channel>,
+}
+
+impl HubReceiver {
+    /// Waits for the next message. Returns `None` once the hub stopped or
+    /// disconnected this consumer, and every buffered message was received.
+    pub fn recv(&self) -> Option<Response> {
+        let mut queue = self.channel.lock();
+        loop {
+            if let Some(response) = queue.buffer.pop_front() {
+                self.channel.changed.notify_all();
+                return Some(response);
+            }
+            if queue.closed {
+                return None;
+            }
+            queue = self
+                .channel
+                .changed
+                .wait(queue)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Like [`recv`](Self::recv), but gives up after `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Response> {
+        let queue = self.channel.lock();
+        let (mut queue, _) = self
+            .channel
+            .changed
+            .wait_timeout_while(queue, timeout, |q| q.buffer.is_empty() && !q.closed)
+            .unwrap_or_else(|e| e.into_inner());
+        let response = queue.buffer.pop_front();
+        self.channel.changed.notify_all();
+        response
+    }
+
+    /// Next message, if one is already buffered.
+    pub fn try_recv(&self) -> Option<Response> {
+        let response = self.channel.lock().buffer.pop_front();
+        self.channel.changed.notify_all();
+        response
+    }
+
+    /// Number of messages that were not delivered because the buffer was full.
+    pub fn dropped(&self) -> u64 {
+        self.channel.lock().dropped
+    }
+
+    /// Whether the hub disconnected this consumer for falling behind.
+    pub fn lagged(&self) -> bool {
+        self.channel.lock().lagged
+    }
+}
+
+impl Drop for HubReceiver {
+    fn drop(&mut self) {
+        self.channel.lock().detached = true;
+        self.channel.changed.notify_all();
+    }
+}
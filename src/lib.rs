@@ -20,6 +20,30 @@
 //! to your environment variables or supply them in-line through the
 //! `BitsoCredentials`' `api_key` and `api_secret` methods.
 //!
+//! ### Blocking client
+//! Every method of [`client::Bitso`] is async. Enable the `blocking` feature
+//! to get `blocking::Bitso`, which has the same methods and models but
+//! blocks the current thread instead.
+//!
+//! ### Books
+//! Books are identified by [`book::Book`], which also accepts books listed
+//! after this crate was released. [`registry::BookRegistry`] loads the books
+//! currently listed by Bitso, along with their trading limits.
+//!
+//! ### Orders
+//! [`order::OrderRequest`] builds market, limit and stop-limit orders and
+//! checks them against a book's limits before
+//! [`place`](client::Bitso::place) signs and sends them.
+//! [`precision::Precision`] rounds prices and amounts to the increments a
+//! book accepts; orders are only checked against the increments set with
+//! [`precision`](client::Bitso::precision) or
+//! [`precisions_from`](client::Bitso::precisions_from).
+//!
+//! ### Async WebSocket
+//! Enable the `async-websocket` feature to get
+//! `async_websocket::AsyncBitsoWebSocket`, a Tokio-based WebSocket client
+//! that implements `futures::Stream`.
+//!
 //! ## Examples
 //! For more detailed information on how to use this wrapper, you could
 //! see the [examples](https://github.com/arturomf94/bitsors/tree/master/examples), which hold detailed use-cases.
@@ -32,12 +56,24 @@ extern crate serde;
 extern crate serde_json;
 #[macro_use]
 extern crate serde_derive;
-#[macro_use]
-extern crate lazy_static;
 extern crate dotenv;
-#[macro_use]
-extern crate anyhow;
+#[cfg(feature = "async-websocket")]
+pub mod async_websocket;
 pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod book;
 pub mod client;
+pub mod error;
+pub mod http;
+pub mod hub;
 pub mod model;
+pub mod order;
+pub mod order_book;
+pub mod precision;
+pub mod rate_limit;
+pub mod recording;
+pub mod registry;
+pub mod retry;
+pub mod transport;
 pub mod websocket;
@@ -0,0 +1,179 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::Infallible;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// Defines an enum of the values Bitso sends for a field, with an `Other`
+/// variant keeping values unknown to this crate.
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $value:literal $(| $alias:literal)*,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Eq)]
+        #[non_exhaustive]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// A value unknown to this crate
+            Other(String),
+        }
+
+        impl $name {
+            /// Value sent to and by Bitso
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Other(value) => value,
+                }
+            }
+
+            /// Whether this crate knows about the value
+            pub fn is_known(&self) -> bool {
+                !matches!(self, $name::Other(_))
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &$name) -> bool {
+                self.as_str() == other.as_str()
+            }
+        }
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.as_str().hash(state)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> $name {
+                $name::from(value.to_owned())
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> $name {
+                match value.to_lowercase().as_str() {
+                    $($value $(| $alias)* => $name::$variant,)*
+                    _ => $name::Other(value),
+                }
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(value: &str) -> Result<$name, Infallible> {
+                Ok($name::from(value))
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$name, D::Error> {
+                Ok($name::from(String::deserialize(deserializer)?))
+            }
+        }
+    };
+}
+
+string_enum! {
+    /// Side of an order or trade.
+    pub enum OrderSide {
+        Buy => "buy",
+        Sell => "sell",
+    }
+}
+
+string_enum! {
+    /// Type of an order.
+    ///
+    /// For more info see: <https://bitso.com/api_info#place-an-order>
+    pub enum OrderType {
+        Market => "market",
+        Limit => "limit",
+    }
+}
+
+string_enum! {
+    /// Status of an order.
+    ///
+    /// For more info see: <https://bitso.com/api_info#lookup-orders>
+    pub enum OrderStatus {
+        Queued => "queued",
+        Open => "open",
+        /// Also read from `partial-fill`, which some endpoints send instead
+        PartiallyFilled => "partially filled" | "partial-fill",
+        Completed => "completed",
+        Cancelled => "cancelled",
+    }
+}
+
+string_enum! {
+    /// How long an order stays in the book.
+    ///
+    /// For more info see: <https://bitso.com/api_info#place-an-order>
+    pub enum TimeInForce {
+        GoodTillCancelled => "goodtillcancelled",
+        FillOrKill => "fillorkill",
+        ImmediateOrCancel => "immediateorcancel",
+        PostOnly => "postonly",
+    }
+}
+
+string_enum! {
+    /// Status of a withdrawal.
+    ///
+    /// For more info see: <https://bitso.com/api_info#withdrawals>
+    pub enum WithdrawalStatus {
+        Pending => "pending",
+        Processing => "processing",
+        Complete => "complete",
+        Failed => "failed",
+    }
+}
+
+string_enum! {
+    /// Status of a funding.
+    ///
+    /// For more info see: <https://bitso.com/api_info#fundings>
+    pub enum FundingStatus {
+        Pending => "pending",
+        Complete => "complete",
+        Cancelled => "cancelled",
+        Failed => "failed",
+    }
+}
+
+string_enum! {
+    /// Operation behind a ledger entry.
+    ///
+    /// For more info see: <https://bitso.com/api_info#ledger>
+    pub enum LedgerOperation {
+        Trade => "trade",
+        Fee => "fee",
+        Funding => "funding",
+        Withdrawal => "withdrawal",
+    }
+}
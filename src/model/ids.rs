@@ -0,0 +1,89 @@
+use std::fmt;
+
+/// Defines a string ID that can only be used where that kind of ID is expected.
+macro_rules! string_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            /// ID as sent by Bitso
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> $name {
+                $name(id.to_owned())
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> $name {
+                $name(id)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> String {
+                id.0
+            }
+        }
+    };
+}
+
+string_id! {
+    /// ID Bitso assigns to an order (`oid`).
+    OrderId
+}
+
+string_id! {
+    /// ID chosen by the client when placing an order or withdrawal (`origin_id`).
+    OriginId
+}
+
+string_id! {
+    /// ID Bitso assigns to a withdrawal (`wid`).
+    WithdrawalId
+}
+
+string_id! {
+    /// ID Bitso assigns to a funding (`fid`).
+    FundingId
+}
+
+/// ID Bitso assigns to a trade (`tid`).
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct TradeId(pub u64);
+
+impl fmt::Display for TradeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<u64> for TradeId {
+    fn from(id: u64) -> TradeId {
+        TradeId(id)
+    }
+}
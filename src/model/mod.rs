@@ -1,9 +1,24 @@
+/// UTC date and time used for every timestamp.
+pub use chrono::{DateTime, Utc};
+/// Exact decimal number used for every price, amount, fee and balance.
+pub use rust_decimal::Decimal;
+
+pub use self::enums::{
+    FundingStatus, LedgerOperation, OrderSide, OrderStatus, OrderType, TimeInForce,
+    WithdrawalStatus,
+};
+pub use self::ids::{FundingId, OrderId, OriginId, TradeId, WithdrawalId};
+pub use self::time::{sort_by_time, Timestamped};
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct JSONResponse<T> {
     pub success: bool,
     pub payload: T,
 }
 
+mod enums;
+mod ids;
 pub mod private;
 pub mod public;
+mod time;
 pub mod websocket;
@@ -1,14 +1,20 @@
+use super::super::book::Book;
+use super::{
+    DateTime, Decimal, FundingId, FundingStatus, LedgerOperation, OrderId, OrderSide, OrderStatus,
+    OrderType, TradeId, Utc, WithdrawalId, WithdrawalStatus,
+};
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AccountStatusPayload {
     pub client_id: Option<String>,
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub status: Option<String>,
-    pub daily_limit: Option<String>,
-    pub daily_remaining: Option<String>,
-    pub monthly_limit: Option<String>,
-    pub monthly_remaining: Option<String>,
-    pub cash_deposit_allowance: Option<String>,
+    pub daily_limit: Option<Decimal>,
+    pub daily_remaining: Option<Decimal>,
+    pub monthly_limit: Option<Decimal>,
+    pub monthly_remaining: Option<Decimal>,
+    pub cash_deposit_allowance: Option<Decimal>,
     pub cellphone_number: Option<String>,
     pub cellphone_number_stored: Option<String>,
     pub email_stored: Option<String>,
@@ -32,11 +38,11 @@ pub struct Balances {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AccountBalanceInstance {
     pub currency: Option<String>,
-    pub available: Option<String>,
-    pub locked: Option<String>,
-    pub total: Option<String>,
-    pub pending_deposit: Option<String>,
-    pub pending_withdrawal: Option<String>,
+    pub available: Option<Decimal>,
+    pub locked: Option<Decimal>,
+    pub total: Option<Decimal>,
+    pub pending_deposit: Option<Decimal>,
+    pub pending_withdrawal: Option<Decimal>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -47,24 +53,24 @@ pub struct FeesPayload {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BookFee {
-    pub book: Option<String>,
-    pub taker_fee_decimal: Option<String>,
-    pub taker_fee_percent: Option<String>,
-    pub maker_fee_decimal: Option<String>,
-    pub maker_fee_percent: Option<String>,
+    pub book: Option<Book>,
+    pub taker_fee_decimal: Option<Decimal>,
+    pub taker_fee_percent: Option<Decimal>,
+    pub maker_fee_decimal: Option<Decimal>,
+    pub maker_fee_percent: Option<Decimal>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WithdrawalFees {
-    pub btc: Option<String>,
-    pub eth: Option<String>,
+    pub btc: Option<Decimal>,
+    pub eth: Option<Decimal>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LedgerInstance {
     pub eid: Option<String>,
-    pub operation: Option<String>,
-    pub created_at: Option<String>,
+    pub operation: Option<LedgerOperation>,
+    pub created_at: Option<DateTime<Utc>>,
     pub balance_updates: Vec<BalanceUpdate>,
     pub details: BalanceDetails,
 }
@@ -72,15 +78,15 @@ pub struct LedgerInstance {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BalanceUpdate {
     pub currency: Option<String>,
-    pub amount: Option<String>,
+    pub amount: Option<Decimal>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BalanceDetails {
-    pub tid: Option<u64>,
-    pub oid: Option<String>,
-    pub fid: Option<String>,
-    pub wid: Option<String>,
+    pub tid: Option<TradeId>,
+    pub oid: Option<OrderId>,
+    pub fid: Option<FundingId>,
+    pub wid: Option<WithdrawalId>,
     pub method: Option<String>,
     pub method_name: Option<String>,
     pub funding_address: Option<String>,
@@ -88,12 +94,12 @@ pub struct BalanceDetails {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WithdrawalsPayload {
-    pub wid: Option<String>,
-    pub status: Option<String>,
-    pub created_at: Option<String>,
+    pub wid: Option<WithdrawalId>,
+    pub status: Option<WithdrawalStatus>,
+    pub created_at: Option<DateTime<Utc>>,
     pub currency: Option<String>,
     pub method: Option<String>,
-    pub amount: Option<String>,
+    pub amount: Option<Decimal>,
     pub details: WithdrawalDetails,
 }
 
@@ -147,12 +153,12 @@ pub struct Cda {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FundingsPayload {
-    pub fid: Option<String>,
-    pub status: Option<String>,
-    pub created_at: Option<String>,
+    pub fid: Option<FundingId>,
+    pub status: Option<FundingStatus>,
+    pub created_at: Option<DateTime<Utc>>,
     pub currency: Option<String>,
     pub method: Option<String>,
-    pub amount: Option<String>,
+    pub amount: Option<Decimal>,
     pub details: FundingDetails,
 }
 
@@ -172,67 +178,80 @@ pub struct FundingDetails {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct UserTradesPayload {
-    pub book: Option<String>,
-    pub major: Option<String>,
-    pub created_at: Option<String>,
-    pub minor: Option<String>,
-    pub fees_amount: Option<String>,
+    pub book: Option<Book>,
+    pub major: Option<Decimal>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub minor: Option<Decimal>,
+    pub fees_amount: Option<Decimal>,
     pub fees_currency: Option<String>,
-    pub price: Option<String>,
-    pub tid: Option<u64>,
-    pub oid: Option<String>,
-    pub side: Option<String>,
+    pub price: Option<Decimal>,
+    pub tid: Option<TradeId>,
+    pub oid: Option<OrderId>,
+    pub side: Option<OrderSide>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OrderTradesPayload {
-    pub book: Option<String>,
-    pub major: Option<String>,
-    pub created_at: Option<String>,
-    pub minor: Option<String>,
-    pub fees_amount: Option<String>,
+    pub book: Option<Book>,
+    pub major: Option<Decimal>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub minor: Option<Decimal>,
+    pub fees_amount: Option<Decimal>,
     pub fees_currency: Option<String>,
-    pub price: Option<String>,
-    pub tid: Option<u64>,
-    pub oid: Option<String>,
+    pub price: Option<Decimal>,
+    pub tid: Option<TradeId>,
+    pub oid: Option<OrderId>,
     pub client_id: Option<String>,
-    pub side: Option<String>,
+    pub side: Option<OrderSide>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OpenOrdersPayload {
-    pub book: Option<String>,
-    pub original_amount: Option<String>,
-    pub unfilled_amount: Option<String>,
-    pub original_value: Option<String>,
-    pub created_at: Option<String>,
-    pub updated_at: Option<String>,
-    pub price: Option<String>,
-    pub oid: Option<String>,
+    pub book: Option<Book>,
+    pub original_amount: Option<Decimal>,
+    pub unfilled_amount: Option<Decimal>,
+    pub original_value: Option<Decimal>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub price: Option<Decimal>,
+    pub oid: Option<OrderId>,
     pub client_id: Option<String>,
-    pub side: Option<String>,
-    pub status: Option<String>,
-    pub r#type: Option<String>,
+    pub side: Option<OrderSide>,
+    pub status: Option<OrderStatus>,
+    pub r#type: Option<OrderType>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LookupOrdersPayload {
-    pub book: Option<String>,
-    pub original_amount: Option<String>,
-    pub unfilled_amount: Option<String>,
-    pub original_value: Option<String>,
-    pub created_at: Option<String>,
-    pub updated_at: Option<String>,
-    pub price: Option<String>,
-    pub oid: Option<String>,
-    pub side: Option<String>,
-    pub status: Option<String>,
-    pub r#type: Option<String>,
+    pub book: Option<Book>,
+    pub original_amount: Option<Decimal>,
+    pub unfilled_amount: Option<Decimal>,
+    pub original_value: Option<Decimal>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub price: Option<Decimal>,
+    pub oid: Option<OrderId>,
+    pub side: Option<OrderSide>,
+    pub status: Option<OrderStatus>,
+    pub r#type: Option<OrderType>,
+}
+
+/// Outcome of [`cancel_order`](crate::client::Bitso::cancel_order).
+///
+/// Bitso lists the orders it cancelled. Order IDs that were requested but
+/// are missing from that list, e.g. because they were already filled or do
+/// not exist, are reported in `not_found`. Bitso does not say which origin
+/// ID each cancelled order had, so `not_found` is `None` when cancelling by
+/// origin ID.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CancelledOrders {
+    pub cancelled: Vec<OrderId>,
+    pub not_found: Option<Vec<OrderId>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PlaceOrderPayload {
-    pub oid: Option<String>,
+    pub oid: Option<OrderId>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -243,12 +262,12 @@ pub struct FundingDestination {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Withdrawal<T> {
-    pub wid: Option<String>,
-    pub status: Option<String>,
-    pub created_at: Option<String>,
+    pub wid: Option<WithdrawalId>,
+    pub status: Option<WithdrawalStatus>,
+    pub created_at: Option<DateTime<Utc>>,
     pub currency: Option<String>,
     pub method: Option<String>,
-    pub amount: Option<String>,
+    pub amount: Option<Decimal>,
     pub details: T,
 }
 
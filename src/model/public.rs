@@ -1,55 +1,63 @@
+use super::super::book::Book;
+use super::{DateTime, Decimal, OrderId, OrderSide, TradeId, Utc};
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AvailableBook {
-    pub book: Option<String>,
-    pub minimum_amount: Option<String>,
-    pub maximum_amount: Option<String>,
-    pub minimum_price: Option<String>,
-    pub maximum_price: Option<String>,
-    pub minimum_value: Option<String>,
-    pub maximum_value: Option<String>,
+    pub book: Option<Book>,
+    pub minimum_amount: Option<Decimal>,
+    pub maximum_amount: Option<Decimal>,
+    pub minimum_price: Option<Decimal>,
+    pub maximum_price: Option<Decimal>,
+    pub minimum_value: Option<Decimal>,
+    pub maximum_value: Option<Decimal>,
+    pub tick_size: Option<Decimal>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BookTicker {
-    pub book: Option<String>,
-    pub volume: Option<String>,
-    pub high: Option<String>,
-    pub last: Option<String>,
-    pub low: Option<String>,
-    pub vwap: Option<String>,
-    pub ask: Option<String>,
-    pub bid: Option<String>,
-    pub created_at: Option<String>,
+    pub book: Option<Book>,
+    pub volume: Option<Decimal>,
+    pub high: Option<Decimal>,
+    pub last: Option<Decimal>,
+    pub low: Option<Decimal>,
+    pub vwap: Option<Decimal>,
+    pub ask: Option<Decimal>,
+    pub bid: Option<Decimal>,
+    pub created_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OrderBookPayload {
     pub asks: Vec<Ask>,
     pub bids: Vec<Bid>,
-    pub updated_at: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
     pub sequence: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Ask {
-    pub book: Option<String>,
-    pub price: Option<String>,
-    pub amount: Option<String>,
+    pub book: Option<Book>,
+    pub price: Option<Decimal>,
+    pub amount: Option<Decimal>,
+    /// Order ID, only present in non-aggregated order books
+    pub oid: Option<OrderId>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Bid {
-    pub book: Option<String>,
-    pub price: Option<String>,
-    pub amount: Option<String>,
+    pub book: Option<Book>,
+    pub price: Option<Decimal>,
+    pub amount: Option<Decimal>,
+    /// Order ID, only present in non-aggregated order books
+    pub oid: Option<OrderId>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Trade {
-    pub book: Option<String>,
-    pub created_at: Option<String>,
-    pub amount: Option<String>,
-    pub maker_side: Option<String>,
-    pub price: Option<String>,
-    pub tid: Option<u64>,
+    pub book: Option<Book>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub amount: Option<Decimal>,
+    pub maker_side: Option<OrderSide>,
+    pub price: Option<Decimal>,
+    pub tid: Option<TradeId>,
 }
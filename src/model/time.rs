@@ -0,0 +1,89 @@
+use super::private::*;
+use super::public::*;
+use super::websocket::{BidAsk, DiffOrdersPayload};
+use super::{DateTime, Utc};
+
+/// A model carrying the time it was created or last updated at.
+///
+/// # Examples
+/// ```
+/// use bitsors::model::private::LedgerInstance;
+/// use bitsors::model::{sort_by_time, Timestamped};
+/// # use bitsors::model::{DateTime, Utc};
+///
+/// # fn entries() -> Vec<LedgerInstance> { Vec::new() }
+/// let since: DateTime<Utc> = "2021-01-01T00:00:00+00:00".parse().unwrap();
+/// let mut ledger: Vec<LedgerInstance> = entries()
+///     .into_iter()
+///     .filter(|entry| entry.is_after(since))
+///     .collect();
+/// sort_by_time(&mut ledger);
+/// ```
+pub trait Timestamped {
+    /// Time of the model, if Bitso sent one
+    fn timestamp(&self) -> Option<DateTime<Utc>>;
+
+    /// Whether the model is timestamped at or after `time`
+    fn is_after(&self, time: DateTime<Utc>) -> bool {
+        self.timestamp().map_or(false, |t| t >= time)
+    }
+
+    /// Whether the model is timestamped before `time`
+    fn is_before(&self, time: DateTime<Utc>) -> bool {
+        self.timestamp().map_or(false, |t| t < time)
+    }
+
+    /// Whether the model is timestamped at or after `start` and before `end`
+    fn is_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        self.is_after(start) && self.is_before(end)
+    }
+}
+
+/// Sorts `items` from oldest to newest. Items without a timestamp come first,
+/// and items with the same timestamp keep their order.
+pub fn sort_by_time<T: Timestamped>(items: &mut [T]) {
+    items.sort_by_key(|item| item.timestamp());
+}
+
+macro_rules! timestamped {
+    ($field:ident: $($model:ty),* $(,)?) => {
+        $(
+            impl Timestamped for $model {
+                fn timestamp(&self) -> Option<DateTime<Utc>> {
+                    self.$field
+                }
+            }
+        )*
+    };
+}
+
+timestamped!(created_at:
+    BookTicker,
+    Trade,
+    LedgerInstance,
+    WithdrawalsPayload,
+    FundingsPayload,
+    UserTradesPayload,
+    OrderTradesPayload,
+    OpenOrdersPayload,
+    LookupOrdersPayload,
+);
+timestamped!(updated_at: OrderBookPayload);
+
+impl<T> Timestamped for Withdrawal<T> {
+    fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+}
+
+impl Timestamped for DiffOrdersPayload {
+    fn timestamp(&self) -> Option<DateTime<Utc>> {
+        Some(self.d)
+    }
+}
+
+impl Timestamped for BidAsk {
+    fn timestamp(&self) -> Option<DateTime<Utc>> {
+        Some(self.d)
+    }
+}
@@ -1,15 +1,5 @@
-use super::super::websocket::Books;
-use serde::{de, Deserialize, Deserializer};
-use std::str::FromStr;
-
-// Custom deserialization implementation for the [Books] enum.
-fn deserialize_books<'de, D>(deserializer: D) -> Result<Books, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let book = <&str>::deserialize(deserializer)?;
-    Books::from_str(book).map_err(de::Error::custom)
-}
+use super::super::book::Book;
+use super::{DateTime, Decimal, OrderId, TradeId, Utc};
 
 // ------------------------------- Trades -------------------------------------
 /// Represents a response from the Trades channel.
@@ -19,8 +9,7 @@ where
 pub struct Trades {
     #[serde(rename = "type")]
     pub type_field: String,
-    #[serde(deserialize_with = "deserialize_books")]
-    pub book: Books,
+    pub book: Book,
     pub payload: Vec<TradesPayload>,
 }
 
@@ -30,13 +19,13 @@ pub struct Trades {
 #[derive(Default, Debug, Clone, PartialEq, serde_derive::Deserialize)]
 pub struct TradesPayload {
     /// A unique number identifying the transaction
-    pub i: i64,
+    pub i: TradeId,
     /// Amount
-    pub a: String,
+    pub a: Decimal,
     /// Rate
-    pub r: String,
+    pub r: Decimal,
     /// Value
-    pub v: String,
+    pub v: Decimal,
 }
 
 // ------------------------------- DiffOrders -------------------------------------
@@ -44,11 +33,11 @@ pub struct TradesPayload {
 /// Represents a response from the Diff-Orders channel.
 ///
 /// For more info see: <https://bitso.com/api_info?#diff-orders>
-#[derive(Default, Debug, Clone, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct DiffOrders {
     #[serde(rename = "type")]
     pub type_field: String,
-    pub book: String,
+    pub book: Book,
     pub payload: Vec<DiffOrdersPayload>,
     pub sequence: i64,
 }
@@ -58,15 +47,21 @@ pub struct DiffOrders {
 /// For more info see: <https://bitso.com/api_info?#diff-orders>
 #[derive(Default, Debug, Clone, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct DiffOrdersPayload {
-    /// Unix timestamp
-    pub d: u64,
+    /// Time of the update, sent as a Unix timestamp in milliseconds
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub d: DateTime<Utc>,
     /// Rate
-    pub r: String,
+    pub r: Decimal,
     /// 0 indicates buy 1 indicates sell
     pub t: u8,
     /// Order ID
-    pub o: String,
+    pub o: OrderId,
+    /// Status: `open`, `cancelled` or `completed`
     pub s: String,
+    /// Amount, missing once the order is no longer open
+    pub a: Option<Decimal>,
+    /// Value, missing once the order is no longer open
+    pub v: Option<Decimal>,
 }
 
 // ------------------------------- Orders -------------------------------------
@@ -74,11 +69,11 @@ pub struct DiffOrdersPayload {
 /// Represents a response from the Orders channel.
 ///
 /// For more info see: <https://bitso.com/api_info?#orders>
-#[derive(Default, Debug, Clone, PartialEq, serde_derive::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde_derive::Deserialize)]
 pub struct Orders {
     #[serde(rename = "type")]
     pub type_field: String,
-    pub book: String,
+    pub book: Book,
     pub payload: OrdersPayload,
 }
 
@@ -97,13 +92,42 @@ pub struct OrdersPayload {
 #[derive(Default, Debug, Clone, PartialEq, serde_derive::Deserialize)]
 pub struct BidAsk {
     /// Rate
-    pub r: f64,
+    pub r: Decimal,
     /// Amount
-    pub a: f64,
+    pub a: Decimal,
     /// Value
-    pub v: f64,
+    pub v: Decimal,
     /// 0 indicates buy 1 indicates sell
     pub t: u8,
+    /// Time of the update, sent as a Unix timestamp in milliseconds
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub d: DateTime<Utc>,
+}
+
+// ------------------------------- Subscriptions ---------------------------------
+
+/// Represents Bitso's answer to a subscription request.
+///
+/// For more info see: <https://bitso.com/api_info?#websocket-api>
+#[derive(Default, Debug, Clone, PartialEq, serde_derive::Deserialize)]
+pub struct SubscriptionAck {
+    /// The requested action, e.g. `subscribe`
+    pub action: String,
+    /// `ok` when the request succeeded
+    pub response: String,
     /// Unix timestamp
-    pub d: u64,
+    pub time: Option<u64>,
+    /// Channel of the request
+    #[serde(rename = "type", default)]
+    pub type_field: String,
+    /// Book of the request. Bitso leaves it out, so it is filled in with the
+    /// requested book when the acknowledgement is returned by `subscribe`.
+    pub book: Option<Book>,
+}
+
+impl SubscriptionAck {
+    /// Whether Bitso accepted the request
+    pub fn is_ok(&self) -> bool {
+        self.response == "ok"
+    }
 }
@@ -0,0 +1,343 @@
+use super::book::Book;
+use super::error::OrderError;
+use super::model::public::AvailableBook;
+use super::model::{Decimal, OrderSide, OrderType, OriginId, TimeInForce};
+use super::precision::{Precision, Rounding};
+use serde_json::map::Map;
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Market,
+    Limit { price: Decimal },
+    StopLimit { price: Decimal, stop: Decimal },
+}
+
+/// Order checked locally before it is sent with
+/// [`place`](crate::client::Bitso::place).
+///
+/// Start from [`market`](Self::market), [`limit`](Self::limit) or
+/// [`stop_limit`](Self::stop_limit) and size the order with either
+/// [`major`](Self::major) or [`minor`](Self::minor). Combinations that Bitso
+/// would reject, such as setting both sizes, are reported as an
+/// [`OrderError`] without making a request.
+///
+/// For more info see: <https://bitso.com/api_info#place-an-order>
+///
+/// # Examples
+/// ```
+/// use bitsors::book::Book;
+/// use bitsors::model::{Decimal, OrderSide};
+/// use bitsors::order::OrderRequest;
+///
+/// let order = OrderRequest::limit(Book::BtcMxn, OrderSide::Buy, Decimal::new(500_000, 0))
+///     .major(Decimal::new(1, 3));
+/// assert!(order.check().is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderRequest {
+    book: Book,
+    side: OrderSide,
+    kind: Kind,
+    major: Option<Decimal>,
+    minor: Option<Decimal>,
+    time_in_force: Option<TimeInForce>,
+    origin_id: Option<OriginId>,
+}
+
+impl OrderRequest {
+    fn new(book: Book, side: OrderSide, kind: Kind) -> OrderRequest {
+        OrderRequest {
+            book,
+            side,
+            kind,
+            major: None,
+            minor: None,
+            time_in_force: None,
+            origin_id: None,
+        }
+    }
+
+    /// Order filled at the best prices available in the book
+    pub fn market(book: Book, side: OrderSide) -> OrderRequest {
+        OrderRequest::new(book, side, Kind::Market)
+    }
+
+    /// Order filled at `price` or better
+    pub fn limit(book: Book, side: OrderSide, price: Decimal) -> OrderRequest {
+        OrderRequest::new(book, side, Kind::Limit { price })
+    }
+
+    /// Limit order at `price` that is only placed once the market reaches `stop`
+    pub fn stop_limit(book: Book, side: OrderSide, price: Decimal, stop: Decimal) -> OrderRequest {
+        OrderRequest::new(book, side, Kind::StopLimit { price, stop })
+    }
+
+    /// Size the order in the major currency (e.g. BTC in `btc_mxn`)
+    pub fn major(mut self, major: Decimal) -> Self {
+        self.major = Some(major);
+        self
+    }
+
+    /// Size the order in the minor currency (e.g. MXN in `btc_mxn`)
+    pub fn minor(mut self, minor: Decimal) -> Self {
+        self.minor = Some(minor);
+        self
+    }
+
+    /// How long a limit order stays open
+    pub fn time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = Some(time_in_force);
+        self
+    }
+
+    /// Client chosen ID used to look up or cancel the order later
+    pub fn origin_id(mut self, origin_id: OriginId) -> Self {
+        self.origin_id = Some(origin_id);
+        self
+    }
+
+    /// Book the order is placed in
+    pub fn book(&self) -> &Book {
+        &self.book
+    }
+
+    /// Whether the order buys or sells the major currency
+    pub fn side(&self) -> &OrderSide {
+        &self.side
+    }
+
+    /// `Market` for market orders, `Limit` for limit and stop-limit orders
+    pub fn order_type(&self) -> OrderType {
+        match self.kind {
+            Kind::Market => OrderType::Market,
+            Kind::Limit { .. } | Kind::StopLimit { .. } => OrderType::Limit,
+        }
+    }
+
+    /// Limit price, or `None` for market orders
+    pub fn price(&self) -> Option<Decimal> {
+        match self.kind {
+            Kind::Market => None,
+            Kind::Limit { price } | Kind::StopLimit { price, .. } => Some(price),
+        }
+    }
+
+    /// Stop price, or `None` unless this is a stop-limit order
+    pub fn stop(&self) -> Option<Decimal> {
+        match self.kind {
+            Kind::StopLimit { stop, .. } => Some(stop),
+            _ => None,
+        }
+    }
+
+    /// Size in the major currency, if the order is sized with [`major`](Self::major)
+    pub fn major_size(&self) -> Option<Decimal> {
+        self.major
+    }
+
+    /// Size in the minor currency, if the order is sized with [`minor`](Self::minor)
+    pub fn minor_size(&self) -> Option<Decimal> {
+        self.minor
+    }
+
+    /// Round the price, stop and size to the increments of `precision`
+    pub fn round(mut self, precision: &Precision, rounding: Rounding) -> Self {
+        self.kind = match self.kind {
+            Kind::Market => Kind::Market,
+            Kind::Limit { price } => Kind::Limit {
+                price: precision.round_price(price, rounding),
+            },
+            Kind::StopLimit { price, stop } => Kind::StopLimit {
+                price: precision.round_price(price, rounding),
+                stop: precision.round_price(stop, rounding),
+            },
+        };
+        self.major = self.major.map(|v| precision.round_amount(v, rounding));
+        self.minor = self.minor.map(|v| precision.round_value(v, rounding));
+        self
+    }
+
+    /// Check that the price, stop and size are multiples of the increments
+    /// of `precision`.
+    ///
+    /// This is run by [`place`](crate::client::Bitso::place) with the
+    /// [precision](crate::client::Bitso::precision_for) configured for the book.
+    pub fn check_precision(&self, precision: &Precision) -> Result<(), OrderError> {
+        check_precision(precision, self.major, self.minor, self.price(), self.stop())
+    }
+
+    /// Check that the order is well formed, without looking at book limits.
+    /// Sides, types and times in force unknown to this crate are rejected.
+    ///
+    /// This is run by [`place`](crate::client::Bitso::place) before signing.
+    pub fn check(&self) -> Result<(), OrderError> {
+        check_known(&self.side, &self.order_type(), self.time_in_force.as_ref())?;
+        match (self.major, self.minor) {
+            (None, None) => return Err(OrderError::MissingSize),
+            (Some(_), Some(_)) => return Err(OrderError::MajorAndMinor),
+            _ => {}
+        }
+        positive("major", self.major)?;
+        positive("minor", self.minor)?;
+        positive("price", self.price())?;
+        positive("stop", self.stop())?;
+        if self.kind == Kind::Market && self.time_in_force.is_some() {
+            return Err(OrderError::TimeInForceOnMarketOrder);
+        }
+        Ok(())
+    }
+
+    /// Check the order and that its amount, price and value fall within the
+    /// limits listed for its book. Prices are also checked against the
+    /// book's `tick_size` when Bitso lists one.
+    ///
+    /// For limit orders the side that was not given is derived from the
+    /// price, so an order sized in `minor` is also checked against the
+    /// amount limits. Market orders are only checked on the size given.
+    pub fn validate(&self, limits: &AvailableBook) -> Result<(), OrderError> {
+        self.check()?;
+        if let Some(book) = &limits.book {
+            if *book != self.book {
+                return Err(OrderError::WrongBook {
+                    order: self.book.clone(),
+                    limits: book.clone(),
+                });
+            }
+        }
+        let price = self.price();
+        let amount = match (self.major, price) {
+            (Some(major), _) => Some(major),
+            (None, Some(price)) => self.minor.and_then(|minor| minor.checked_div(price)),
+            (None, None) => None,
+        };
+        let value = match (self.minor, price) {
+            (Some(minor), _) => Some(minor),
+            (None, Some(price)) => self.major.and_then(|major| major.checked_mul(price)),
+            (None, None) => None,
+        };
+        within(
+            "amount",
+            amount,
+            limits.minimum_amount,
+            limits.maximum_amount,
+        )?;
+        within("price", price, limits.minimum_price, limits.maximum_price)?;
+        within(
+            "stop",
+            self.stop(),
+            limits.minimum_price,
+            limits.maximum_price,
+        )?;
+        within("value", value, limits.minimum_value, limits.maximum_value)?;
+        self.check_precision(&Precision::from_available_book(limits))
+    }
+
+    /// Body of the signed POST to `/v3/orders/`
+    pub(crate) fn to_params(&self) -> Value {
+        let mut params = Map::new();
+        params.insert("book".to_owned(), Value::String(self.book.to_string()));
+        params.insert("side".to_owned(), Value::String(self.side.to_string()));
+        params.insert(
+            "type".to_owned(),
+            Value::String(self.order_type().to_string()),
+        );
+        let optional = [
+            ("major", self.major.map(|v| v.to_string())),
+            ("minor", self.minor.map(|v| v.to_string())),
+            ("price", self.price().map(|v| v.to_string())),
+            ("stop", self.stop().map(|v| v.to_string())),
+            (
+                "time_in_force",
+                self.time_in_force.as_ref().map(|v| v.to_string()),
+            ),
+            ("origin_id", self.origin_id.as_ref().map(|v| v.to_string())),
+        ];
+        for (key, value) in optional.iter() {
+            if let Some(value) = value {
+                params.insert((*key).to_owned(), Value::String(value.clone()));
+            }
+        }
+        Value::Object(params)
+    }
+}
+
+/// Check that the side, type and time in force are values Bitso accepts
+pub(crate) fn check_known(
+    side: &OrderSide,
+    order_type: &OrderType,
+    time_in_force: Option<&TimeInForce>,
+) -> Result<(), OrderError> {
+    let unknown = |field, value: &str| OrderError::Unknown {
+        field,
+        value: value.to_owned(),
+    };
+    if !side.is_known() {
+        return Err(unknown("side", side.as_str()));
+    }
+    if !order_type.is_known() {
+        return Err(unknown("type", order_type.as_str()));
+    }
+    match time_in_force {
+        Some(tif) if !tif.is_known() => Err(unknown("time in force", tif.as_str())),
+        _ => Ok(()),
+    }
+}
+
+/// Check sizes and prices against the increments of a book
+pub(crate) fn check_precision(
+    precision: &Precision,
+    major: Option<Decimal>,
+    minor: Option<Decimal>,
+    price: Option<Decimal>,
+    stop: Option<Decimal>,
+) -> Result<(), OrderError> {
+    let checks = [
+        ("major", major, precision.amount_tick),
+        ("minor", minor, precision.value_tick),
+        ("price", price, precision.price_tick),
+        ("stop", stop, precision.price_tick),
+    ];
+    for (field, value, tick) in checks.iter() {
+        if let Some(value) = *value {
+            if !Precision::is_multiple(value, *tick) {
+                return Err(OrderError::TooPrecise {
+                    field,
+                    value,
+                    tick: *tick,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn positive(field: &'static str, value: Option<Decimal>) -> Result<(), OrderError> {
+    match value {
+        Some(value) if value <= Decimal::ZERO => Err(OrderError::NotPositive { field, value }),
+        _ => Ok(()),
+    }
+}
+
+fn within(
+    field: &'static str,
+    value: Option<Decimal>,
+    minimum: Option<Decimal>,
+    maximum: Option<Decimal>,
+) -> Result<(), OrderError> {
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    let below = minimum.map_or(false, |min| value < min);
+    let above = maximum.map_or(false, |max| value > max);
+    if below || above {
+        return Err(OrderError::OutOfRange {
+            field,
+            value,
+            minimum,
+            maximum,
+        });
+    }
+    Ok(())
+}
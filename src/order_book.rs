@@ -0,0 +1,379 @@
+use super::book::Book;
+use super::client::Bitso;
+use super::error::{required, BitsoError, Result};
+use super::model::public::OrderBookPayload;
+use super::model::websocket::{DiffOrders, DiffOrdersPayload};
+use super::model::{Decimal, OrderId};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Longest wait between two snapshots while out of sync.
+const MAX_RESYNC_DELAY: Duration = Duration::from_secs(60);
+
+/// Default number of diffs buffered while out of sync.
+const MAX_PENDING: usize = 10_000;
+
+/// Side of the book an order rests on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+/// Aggregated amount resting at a price.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PriceLevel {
+    pub price: Decimal,
+    pub amount: Decimal,
+    /// Number of orders at this price
+    pub orders: usize,
+}
+
+/// Outcome of applying a diff to a [`LocalOrderBook`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BookUpdate {
+    /// The diff was the next one in sequence and was applied.
+    Applied,
+    /// The diff was older than the current state and was dropped.
+    Stale,
+    /// A sequence gap was found and the book was rebuilt from a new snapshot.
+    Resynced,
+    /// A sequence gap was found but the new snapshot is still behind the
+    /// stream. Diffs are buffered until a later snapshot catches up.
+    /// Snapshots are fetched again with a growing delay, not on every diff.
+    OutOfSync,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Order {
+    side: Side,
+    price: Decimal,
+    amount: Decimal,
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+struct Level {
+    amount: Decimal,
+    orders: usize,
+}
+
+/// Order book kept up to date from the
+/// [Diff-Orders](https://bitso.com/api_info?#diff-orders) channel.
+///
+/// The book starts from a non-aggregated REST snapshot and applies every
+/// [`DiffOrders`] message in sequence order. Diffs already covered by the
+/// current state are dropped, and a missing sequence number makes the book
+/// fetch a new snapshot on its own. If that snapshot is still behind the
+/// stream, later diffs are only buffered and a new snapshot is fetched after
+/// [`resync_delay`](Self::resync_delay), doubling on every miss.
+///
+/// For more info see: <https://bitso.com/api_info?#how-to-keep-an-order-book>
+///
+/// # Examples
+/// ```no_run
+/// use bitsors::client::Bitso;
+/// use bitsors::order_book::LocalOrderBook;
+/// use bitsors::websocket::*;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let mut socket = BitsoWebSocket::new().unwrap();
+/// socket.subscribe(Subscription::DiffOrders, Book::BtcMxn).unwrap();
+///
+/// let mut book = LocalOrderBook::new(Bitso::default(), &Book::BtcMxn).await.unwrap();
+/// loop {
+///     if let Response::DiffOrders(diff) = socket.read().unwrap() {
+///         book.apply(&diff).await.unwrap();
+///         println!("{:?} / {:?}", book.best_bid(), book.best_ask());
+///     }
+/// }
+/// # }
+/// ```
+pub struct LocalOrderBook {
+    client: Bitso,
+    book: Book,
+    sequence: i64,
+    synced: bool,
+    pending: Vec<DiffOrders>,
+    max_pending: usize,
+    resync_delay: Duration,
+    retry_delay: Duration,
+    next_resync: Option<Instant>,
+    orders: HashMap<OrderId, Order>,
+    bids: BTreeMap<Decimal, Level>,
+    asks: BTreeMap<Decimal, Level>,
+}
+
+impl fmt::Debug for LocalOrderBook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LocalOrderBook")
+            .field("book", &self.book)
+            .field("sequence", &self.sequence)
+            .field("synced", &self.synced)
+            .field("bids", &self.bids.len())
+            .field("asks", &self.asks.len())
+            .field("pending", &self.pending.len())
+            .finish()
+    }
+}
+
+impl LocalOrderBook {
+    /// Fetches a snapshot of `book` and builds a local order book out of it.
+    pub async fn new(client: Bitso, book: &Book) -> Result<LocalOrderBook> {
+        let snapshot = client.get_order_book(book, false).await?.payload;
+        LocalOrderBook::from_snapshot(client, book, snapshot)
+    }
+
+    /// Builds a local order book out of a non-aggregated snapshot. `client`
+    /// is used to fetch a new snapshot on sequence gaps.
+    pub fn from_snapshot(
+        client: Bitso,
+        book: &Book,
+        snapshot: OrderBookPayload,
+    ) -> Result<LocalOrderBook> {
+        let mut order_book = LocalOrderBook {
+            client,
+            book: book.clone(),
+            sequence: 0,
+            synced: true,
+            pending: Vec::new(),
+            max_pending: MAX_PENDING,
+            resync_delay: Duration::from_secs(1),
+            retry_delay: Duration::from_secs(1),
+            next_resync: None,
+            orders: HashMap::new(),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+        };
+        order_book.reset(snapshot)?;
+        Ok(order_book)
+    }
+
+    /// Set how long to wait before fetching another snapshot when the last
+    /// one was still behind the stream. The delay doubles on every miss, up
+    /// to a minute. Defaults to one second.
+    pub fn resync_delay(mut self, delay: Duration) -> LocalOrderBook {
+        self.resync_delay = delay;
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Set how many diffs are buffered while out of sync. Once full, the
+    /// buffer is dropped and the book waits for a snapshot that catches up
+    /// with the stream on its own. Defaults to 10,000.
+    pub fn max_pending(mut self, max: usize) -> LocalOrderBook {
+        self.max_pending = max;
+        self
+    }
+
+    /// Applies a message from the Diff-Orders channel.
+    pub async fn apply(&mut self, diff: &DiffOrders) -> Result<BookUpdate> {
+        if diff.book != self.book {
+            return Err(BitsoError::Validation(format!(
+                "diff for book {} applied to the {} order book",
+                diff.book, self.book
+            )));
+        }
+        if self.synced {
+            if diff.sequence <= self.sequence {
+                return Ok(BookUpdate::Stale);
+            }
+            if diff.sequence == self.sequence + 1 {
+                self.apply_payload(diff)?;
+                return Ok(BookUpdate::Applied);
+            }
+            self.synced = false;
+        }
+        if self.pending.len() >= self.max_pending {
+            self.pending.clear();
+        }
+        self.pending.push(diff.clone());
+        match self.next_resync {
+            Some(at) if Instant::now() < at => Ok(BookUpdate::OutOfSync),
+            _ => self.resync().await,
+        }
+    }
+
+    /// Fetches a new snapshot and replays the buffered diffs on top of it.
+    async fn resync(&mut self) -> Result<BookUpdate> {
+        // Wait before the next attempt, even if this one fails to fetch
+        self.next_resync = Some(Instant::now() + self.retry_delay);
+        self.retry_delay = self
+            .retry_delay
+            .checked_mul(2)
+            .map_or(MAX_RESYNC_DELAY, |delay| delay.min(MAX_RESYNC_DELAY));
+        let snapshot = self.client.get_order_book(&self.book, false).await?.payload;
+        self.reset(snapshot)?;
+        let sequence = self.sequence;
+        self.pending.retain(|diff| diff.sequence > sequence);
+        self.pending.sort_by_key(|diff| diff.sequence);
+        self.pending.dedup_by_key(|diff| diff.sequence);
+        if self
+            .pending
+            .iter()
+            .zip(sequence + 1..)
+            .any(|(d, s)| d.sequence != s)
+        {
+            self.synced = false;
+            return Ok(BookUpdate::OutOfSync);
+        }
+        for diff in std::mem::take(&mut self.pending) {
+            self.apply_payload(&diff)?;
+        }
+        self.next_resync = None;
+        self.retry_delay = self.resync_delay;
+        Ok(BookUpdate::Resynced)
+    }
+
+    fn reset(&mut self, snapshot: OrderBookPayload) -> Result<()> {
+        let sequence = snapshot.sequence.as_deref().unwrap_or_default();
+        self.sequence = sequence.parse().map_err(|_| {
+            BitsoError::Validation(format!("invalid order book sequence {:?}", sequence))
+        })?;
+        self.synced = true;
+        self.orders.clear();
+        self.bids.clear();
+        self.asks.clear();
+        let bids = snapshot
+            .bids
+            .into_iter()
+            .map(|b| (Side::Bid, b.oid, b.price, b.amount));
+        let asks = snapshot
+            .asks
+            .into_iter()
+            .map(|a| (Side::Ask, a.oid, a.price, a.amount));
+        for (side, oid, price, amount) in bids.chain(asks) {
+            let oid = oid.ok_or_else(|| {
+                BitsoError::Validation("order book snapshot must not be aggregated".to_owned())
+            })?;
+            let price = required("price", price)?;
+            let amount = required("amount", amount)?;
+            self.insert(
+                oid,
+                Order {
+                    side,
+                    price,
+                    amount,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    fn apply_payload(&mut self, diff: &DiffOrders) -> Result<()> {
+        for order in &diff.payload {
+            self.apply_order(order)?;
+        }
+        self.sequence = diff.sequence;
+        Ok(())
+    }
+
+    fn apply_order(&mut self, order: &DiffOrdersPayload) -> Result<()> {
+        self.remove(&order.o);
+        if order.s != "open" {
+            return Ok(());
+        }
+        let amount = required("amount", order.a)?;
+        if amount <= Decimal::ZERO {
+            return Ok(());
+        }
+        let side = match order.t {
+            0 => Side::Bid,
+            _ => Side::Ask,
+        };
+        let price = order.r;
+        self.insert(
+            order.o.clone(),
+            Order {
+                side,
+                price,
+                amount,
+            },
+        );
+        Ok(())
+    }
+
+    fn levels(&mut self, side: Side) -> &mut BTreeMap<Decimal, Level> {
+        match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        }
+    }
+
+    fn insert(&mut self, oid: OrderId, order: Order) {
+        let level = self.levels(order.side).entry(order.price).or_default();
+        level.amount += order.amount;
+        level.orders += 1;
+        self.orders.insert(oid, order);
+    }
+
+    fn remove(&mut self, oid: &OrderId) {
+        let order = match self.orders.remove(oid) {
+            Some(order) => order,
+            None => return,
+        };
+        let levels = self.levels(order.side);
+        if let Some(level) = levels.get_mut(&order.price) {
+            level.orders -= 1;
+            level.amount -= order.amount;
+            if level.orders == 0 {
+                levels.remove(&order.price);
+            }
+        }
+    }
+
+    /// Book this order book tracks
+    pub fn book(&self) -> &Book {
+        &self.book
+    }
+
+    /// Sequence number of the last applied update
+    pub fn sequence(&self) -> i64 {
+        self.sequence
+    }
+
+    /// Whether the book is in sync with the stream. While out of sync, the
+    /// book keeps the state of the last snapshot it could apply.
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    /// Number of diffs buffered until the book is in sync again
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Highest bid
+    pub fn best_bid(&self) -> Option<PriceLevel> {
+        self.bids(1).into_iter().next()
+    }
+
+    /// Lowest ask
+    pub fn best_ask(&self) -> Option<PriceLevel> {
+        self.asks(1).into_iter().next()
+    }
+
+    /// Up to `depth` bid levels, best first
+    pub fn bids(&self, depth: usize) -> Vec<PriceLevel> {
+        self.bids
+            .iter()
+            .rev()
+            .take(depth)
+            .map(price_level)
+            .collect()
+    }
+
+    /// Up to `depth` ask levels, best first
+    pub fn asks(&self, depth: usize) -> Vec<PriceLevel> {
+        self.asks.iter().take(depth).map(price_level).collect()
+    }
+}
+
+fn price_level((price, level): (&Decimal, &Level)) -> PriceLevel {
+    PriceLevel {
+        price: *price,
+        amount: level.amount,
+        orders: level.orders,
+    }
+}
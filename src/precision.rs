@@ -0,0 +1,150 @@
+use super::book::Book;
+use super::model::public::AvailableBook;
+use super::model::Decimal;
+use rust_decimal::RoundingStrategy;
+
+/// Currencies Bitso quotes with two decimal places.
+const FIAT: &[&str] = &["ars", "brl", "cop", "eur", "mxn", "usd"];
+
+/// Direction used when a price or amount is not a multiple of its tick.
+///
+/// Values too large to be divided by their tick are left unchanged.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Rounding {
+    /// Towards zero, e.g. to never spend more than intended
+    Down,
+    /// Away from zero
+    Up,
+    /// To the closest tick, halfway values away from zero
+    Nearest,
+}
+
+/// Smallest increments a book accepts for prices and sizes.
+///
+/// Bitso rejects orders with more decimal places than the book allows.
+/// [`from_available_book`](Self::from_available_book) only uses the
+/// `tick_size` Bitso lists for the book. [`for_book`](Self::for_book) is an
+/// opt-in guess that assumes two decimals for fiat currencies and eight for
+/// crypto currencies. Either can be adjusted with the setters. A tick of
+/// zero accepts any value.
+///
+/// # Examples
+/// ```
+/// use bitsors::book::Book;
+/// use bitsors::model::Decimal;
+/// use bitsors::precision::{Precision, Rounding};
+///
+/// let precision = Precision::for_book(&Book::BtcMxn).price_tick(Decimal::new(10, 0));
+/// let price = precision.round_price(Decimal::new(5000015, 1), Rounding::Down);
+/// assert_eq!(price, Decimal::new(500000, 0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Precision {
+    /// Increment of prices, in the minor currency
+    pub price_tick: Decimal,
+    /// Increment of `major` amounts
+    pub amount_tick: Decimal,
+    /// Increment of `minor` amounts
+    pub value_tick: Decimal,
+}
+
+impl Precision {
+    /// Rules guessed from the currencies of a book
+    pub fn for_book(book: &Book) -> Precision {
+        let quote = currency_tick(book.quote());
+        Precision {
+            price_tick: quote,
+            amount_tick: currency_tick(book.base()),
+            value_tick: quote,
+        }
+    }
+
+    /// Rules for a book as listed by
+    /// [`get_available_books`](crate::client::Bitso::get_available_books).
+    /// Prices use the listed `tick_size`, if any, and sizes are not checked.
+    pub fn from_available_book(available: &AvailableBook) -> Precision {
+        Precision {
+            price_tick: available
+                .tick_size
+                .filter(|tick| *tick > Decimal::ZERO)
+                .unwrap_or(Decimal::ZERO),
+            amount_tick: Decimal::ZERO,
+            value_tick: Decimal::ZERO,
+        }
+    }
+
+    /// Set the price increment
+    pub fn price_tick(mut self, tick: Decimal) -> Self {
+        self.price_tick = tick;
+        self
+    }
+
+    /// Set the `major` amount increment
+    pub fn amount_tick(mut self, tick: Decimal) -> Self {
+        self.amount_tick = tick;
+        self
+    }
+
+    /// Set the `minor` amount increment
+    pub fn value_tick(mut self, tick: Decimal) -> Self {
+        self.value_tick = tick;
+        self
+    }
+
+    /// Round a price to a multiple of `price_tick`
+    pub fn round_price(&self, price: Decimal, rounding: Rounding) -> Decimal {
+        round_to(price, self.price_tick, rounding)
+    }
+
+    /// Round a `major` amount to a multiple of `amount_tick`
+    pub fn round_amount(&self, amount: Decimal, rounding: Rounding) -> Decimal {
+        round_to(amount, self.amount_tick, rounding)
+    }
+
+    /// Round a `minor` amount to a multiple of `value_tick`
+    pub fn round_value(&self, value: Decimal, rounding: Rounding) -> Decimal {
+        round_to(value, self.value_tick, rounding)
+    }
+
+    /// Whether a price is a multiple of `price_tick`
+    pub fn is_valid_price(&self, price: Decimal) -> bool {
+        Precision::is_multiple(price, self.price_tick)
+    }
+
+    /// Whether a `major` amount is a multiple of `amount_tick`
+    pub fn is_valid_amount(&self, amount: Decimal) -> bool {
+        Precision::is_multiple(amount, self.amount_tick)
+    }
+
+    /// Whether a `minor` amount is a multiple of `value_tick`
+    pub fn is_valid_value(&self, value: Decimal) -> bool {
+        Precision::is_multiple(value, self.value_tick)
+    }
+
+    pub(crate) fn is_multiple(value: Decimal, tick: Decimal) -> bool {
+        tick <= Decimal::ZERO || (value % tick).is_zero()
+    }
+}
+
+fn currency_tick(currency: &str) -> Decimal {
+    if FIAT.contains(&currency) {
+        Decimal::new(1, 2)
+    } else {
+        Decimal::new(1, 8)
+    }
+}
+
+fn round_to(value: Decimal, tick: Decimal, rounding: Rounding) -> Decimal {
+    if tick <= Decimal::ZERO {
+        return value;
+    }
+    let strategy = match rounding {
+        Rounding::Down => RoundingStrategy::ToZero,
+        Rounding::Up => RoundingStrategy::AwayFromZero,
+        Rounding::Nearest => RoundingStrategy::MidpointAwayFromZero,
+    };
+    value
+        .checked_div(tick)
+        .and_then(|ticks| ticks.round_dp_with_strategy(0, strategy).checked_mul(tick))
+        .map_or(value, |rounded| rounded.normalize())
+}
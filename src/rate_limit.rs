@@ -0,0 +1,193 @@
+use super::client::ApiType;
+use super::error::{BitsoError, Result};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Number of requests allowed over a period of time.
+///
+/// For more info see: <https://bitso.com/api_info#rate-limits>
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Budget {
+    pub requests: u32,
+    pub period: Duration,
+}
+
+impl Budget {
+    /// Budget of `requests` over `period`. Fails if `period` is zero.
+    pub fn new(requests: u32, period: Duration) -> Result<Budget> {
+        if period == Duration::from_secs(0) {
+            return Err(BitsoError::Validation(
+                "rate limit budget period must not be zero".to_owned(),
+            ));
+        }
+        Ok(Budget {
+            requests: requests.max(1),
+            period,
+        })
+    }
+
+    /// Budget of `requests` per minute
+    pub fn per_minute(requests: u32) -> Budget {
+        Budget {
+            requests: requests.max(1),
+            period: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Counters describing how much a [`RateLimiter`] has throttled requests.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct RateLimiterStats {
+    /// Requests that went through the limiter.
+    pub requests: u64,
+    /// Requests that had to wait for the budget to refill.
+    pub throttled: u64,
+    /// Time spent waiting, summed over all requests.
+    pub total_wait: Duration,
+    /// Time the most recent request waited.
+    pub last_wait: Duration,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    budget: Budget,
+    tokens: f64,
+    updated_at: Instant,
+    stats: RateLimiterStats,
+}
+
+impl Bucket {
+    fn new(budget: Budget) -> Bucket {
+        Bucket {
+            budget,
+            tokens: f64::from(budget.requests),
+            updated_at: Instant::now(),
+            stats: RateLimiterStats::default(),
+        }
+    }
+
+    /// Takes one token and returns how long the caller must wait for it.
+    /// Tokens may go negative, so concurrent callers queue up in order.
+    fn reserve(&mut self) -> Duration {
+        let now = Instant::now();
+        if self.budget.period == Duration::from_secs(0) {
+            // Budgets built by hand with a zero period never throttle
+            self.stats.requests += 1;
+            self.stats.last_wait = Duration::from_secs(0);
+            return Duration::from_secs(0);
+        }
+        let capacity = f64::from(self.budget.requests);
+        let rate = capacity / self.budget.period.as_secs_f64();
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(capacity);
+        self.updated_at = now;
+        self.tokens -= 1.0;
+        let wait = if self.tokens < 0.0 {
+            Duration::from_secs_f64(-self.tokens / rate)
+        } else {
+            Duration::from_secs(0)
+        };
+        self.stats.requests += 1;
+        if wait > Duration::from_secs(0) {
+            self.stats.throttled += 1;
+        }
+        self.stats.total_wait += wait;
+        self.stats.last_wait = wait;
+        wait
+    }
+}
+
+/// Client-side token-bucket limiter with separate budgets for the
+/// public and private APIs.
+///
+/// A limiter is shared by every clone of the [`Bitso`](crate::client::Bitso)
+/// object it is attached to, so several tasks can use the same budget.
+/// Bitso objects do not throttle unless a limiter is set, e.g.
+/// `RateLimiter::default()` for Bitso's documented budgets.
+///
+/// # Examples
+/// ```
+/// use bitsors::client::Bitso;
+/// use bitsors::rate_limit::{Budget, RateLimiter};
+///
+/// let limiter = RateLimiter::default()
+///     .private_budget(Some(Budget::per_minute(100)))
+///     .build();
+/// let bitso = Bitso::default().rate_limiter(limiter).build();
+/// ```
+#[derive(Debug)]
+pub struct RateLimiter {
+    public: Option<Mutex<Bucket>>,
+    private: Option<Mutex<Bucket>>,
+}
+
+impl Default for RateLimiter {
+    /// Limiter using Bitso's documented budgets: 60 public
+    /// and 300 private requests per minute.
+    fn default() -> RateLimiter {
+        RateLimiter {
+            public: Some(Mutex::new(Bucket::new(Budget::per_minute(60)))),
+            private: Some(Mutex::new(Bucket::new(Budget::per_minute(300)))),
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Limiter that never throttles.
+    pub fn unlimited() -> RateLimiter {
+        RateLimiter {
+            public: None,
+            private: None,
+        }
+    }
+
+    /// Set the public API budget. `None` disables throttling for it.
+    pub fn public_budget(mut self, budget: Option<Budget>) -> RateLimiter {
+        self.public = budget.map(|b| Mutex::new(Bucket::new(b)));
+        self
+    }
+
+    /// Set the private API budget. `None` disables throttling for it.
+    pub fn private_budget(mut self, budget: Option<Budget>) -> RateLimiter {
+        self.private = budget.map(|b| Mutex::new(Bucket::new(b)));
+        self
+    }
+
+    /// Build RateLimiter object
+    pub fn build(self) -> RateLimiter {
+        self
+    }
+
+    fn bucket(&self, api_type: ApiType) -> Option<&Mutex<Bucket>> {
+        match api_type {
+            ApiType::Public => self.public.as_ref(),
+            ApiType::Private => self.private.as_ref(),
+        }
+    }
+
+    /// Budget configured for the given API, if any.
+    pub fn budget(&self, api_type: ApiType) -> Option<Budget> {
+        self.bucket(api_type)
+            .map(|b| b.lock().unwrap_or_else(|e| e.into_inner()).budget)
+    }
+
+    /// Waits until a request to the given API fits in its budget
+    /// and returns how long the caller waited.
+    pub async fn acquire(&self, api_type: ApiType) -> Duration {
+        let wait = match self.bucket(api_type) {
+            Some(bucket) => bucket.lock().unwrap_or_else(|e| e.into_inner()).reserve(),
+            None => return Duration::from_secs(0),
+        };
+        if wait > Duration::from_secs(0) {
+            tokio::time::sleep(wait).await;
+        }
+        wait
+    }
+
+    /// Throttling counters for the given API.
+    pub fn stats(&self, api_type: ApiType) -> RateLimiterStats {
+        self.bucket(api_type)
+            .map(|b| b.lock().unwrap_or_else(|e| e.into_inner()).stats)
+            .unwrap_or_default()
+    }
+}
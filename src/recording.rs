@@ -0,0 +1,240 @@
+use super::error::{BitsoError, Result};
+use super::model::websocket::SubscriptionAck;
+use super::websocket::{parse_message, Book, Response, ResponseSource, Subscription};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Kind of a recorded WebSocket frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameKind {
+    /// Text or binary frame
+    Data,
+    Ping,
+    Pong,
+    /// The server closed the connection
+    Close,
+}
+
+impl Default for FrameKind {
+    fn default() -> FrameKind {
+        FrameKind::Data
+    }
+}
+
+impl FrameKind {
+    fn is_data(&self) -> bool {
+        *self == FrameKind::Data
+    }
+}
+
+/// A raw WebSocket frame, as stored on each line of a recording.
+///
+/// Text frames are kept in `text`. Binary frames are kept in `text` too when
+/// they are valid UTF-8, and as raw bytes in `binary` otherwise. Control
+/// frames also store their `kind`, which is left out for data frames.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// Unix timestamp in milliseconds at which the frame was received
+    pub received_at: u64,
+    #[serde(default, skip_serializing_if = "FrameKind::is_data")]
+    pub kind: FrameKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary: Option<Vec<u8>>,
+}
+
+impl RecordedFrame {
+    /// Data frame received now with the given payload
+    pub fn new(data: Vec<u8>) -> RecordedFrame {
+        RecordedFrame::control(FrameKind::Data, data)
+    }
+
+    /// Frame of the given kind received now with the given payload
+    pub fn control(kind: FrameKind, data: Vec<u8>) -> RecordedFrame {
+        let received_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let (text, binary) = match String::from_utf8(data) {
+            Ok(text) => (Some(text), None),
+            Err(e) => (None, Some(e.into_bytes())),
+        };
+        RecordedFrame {
+            received_at,
+            kind,
+            text,
+            binary,
+        }
+    }
+
+    /// Payload of the frame
+    pub fn data(&self) -> &[u8] {
+        match (&self.text, &self.binary) {
+            (Some(text), _) => text.as_bytes(),
+            (None, Some(binary)) => binary,
+            (None, None) => &[],
+        }
+    }
+}
+
+/// Writes every frame received by a [`BitsoWebSocket`](crate::websocket::BitsoWebSocket)
+/// as a line of JSON.
+pub(crate) struct Recorder {
+    writer: Box<dyn Write + Send>,
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Recorder").finish()
+    }
+}
+
+impl Recorder {
+    pub(crate) fn new<W: Write + Send + 'static>(writer: W) -> Recorder {
+        Recorder {
+            writer: Box::new(writer),
+        }
+    }
+
+    pub(crate) fn create<P: AsRef<Path>>(path: P) -> Result<Recorder> {
+        Ok(Recorder::new(BufWriter::new(File::create(path)?)))
+    }
+
+    /// Writes the frame and flushes it, so the recording survives a crash.
+    pub(crate) fn record(&mut self, frame: &RecordedFrame) -> Result<()> {
+        let line = serde_json::to_string(frame).map_err(|source| BitsoError::Decode {
+            source,
+            body: format!("{:?}", frame),
+        })?;
+        writeln!(self.writer, "{}", line)?;
+        Ok(self.writer.flush()?)
+    }
+}
+
+/// Plays back a recording made with
+/// [`BitsoWebSocket::record`](crate::websocket::BitsoWebSocket::record).
+///
+/// Frames go through the same parsing as a live connection, and a replay can
+/// be used wherever a [`ResponseSource`] is expected. By default frames are
+/// returned as fast as possible; [`real_time`](Self::real_time) keeps the
+/// recorded delay between them. A recorded close frame makes
+/// [`read`](ResponseSource::read) fail as if the connection had closed, and
+/// reading goes on with the frames recorded after it. Once every frame has
+/// been read, `read` fails the same way.
+///
+/// A replay is not a full stand-in for a live connection: it never reports
+/// [`BitsoError::Stale`] or [`Response::Reconnected`], and a
+/// [`LocalOrderBook`](crate::order_book::LocalOrderBook) fed from it still
+/// fetches snapshots over REST on sequence gaps, so give it a client backed
+/// by a [`MemoryTransport`](crate::transport::MemoryTransport) to stay offline.
+///
+/// # Examples
+/// ```no_run
+/// use bitsors::recording::Replay;
+/// use bitsors::websocket::*;
+///
+/// let mut replay = Replay::open("btc_mxn.jsonl").unwrap().real_time(true);
+/// while let Ok(response) = replay.read() {
+///     println!("{:?}", response);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Replay<R> {
+    lines: Lines<R>,
+    real_time: bool,
+    started: Option<(u64, Instant)>,
+}
+
+impl Replay<BufReader<File>> {
+    /// Replays the recording stored at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Replay<BufReader<File>>> {
+        Ok(Replay::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl<R: BufRead> Replay<R> {
+    /// Replays the recording read from `reader`
+    pub fn new(reader: R) -> Replay<R> {
+        Replay {
+            lines: reader.lines(),
+            real_time: false,
+            started: None,
+        }
+    }
+
+    /// Set whether frames are returned with their recorded delays
+    pub fn real_time(mut self, real_time: bool) -> Replay<R> {
+        self.real_time = real_time;
+        self
+    }
+
+    /// Next raw frame of the recording, or `None` at its end.
+    pub fn next_frame(&mut self) -> Result<Option<RecordedFrame>> {
+        let line = loop {
+            match self.lines.next() {
+                Some(line) => {
+                    let line = line?;
+                    if !line.trim().is_empty() {
+                        break line;
+                    }
+                }
+                None => return Ok(None),
+            }
+        };
+        let frame: RecordedFrame = serde_json::from_str(&line)
+            .map_err(|source| BitsoError::Decode { source, body: line })?;
+        if self.real_time {
+            let (first, started) = *self
+                .started
+                .get_or_insert((frame.received_at, Instant::now()));
+            let due = started + Duration::from_millis(frame.received_at.saturating_sub(first));
+            let now = Instant::now();
+            if due > now {
+                thread::sleep(due - now);
+            }
+        }
+        Ok(Some(frame))
+    }
+}
+
+impl<R: BufRead> ResponseSource for Replay<R> {
+    /// Subscriptions are not replayed; every recorded frame is returned.
+    fn subscribe(
+        &mut self,
+        subscription_type: Subscription,
+        book: Book,
+    ) -> Result<SubscriptionAck> {
+        Ok(SubscriptionAck {
+            action: "subscribe".to_owned(),
+            response: "ok".to_owned(),
+            time: None,
+            type_field: subscription_type.to_string(),
+            book: Some(book),
+        })
+    }
+
+    fn read(&mut self) -> Result<Response> {
+        while let Some(frame) = self.next_frame()? {
+            match frame.kind {
+                FrameKind::Data => {
+                    if let Some(response) = parse_message(frame.data())? {
+                        return Ok(response);
+                    }
+                }
+                FrameKind::Ping | FrameKind::Pong => {}
+                FrameKind::Close => return Err(tungstenite::Error::ConnectionClosed.into()),
+            }
+        }
+        Err(tungstenite::Error::ConnectionClosed.into())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use super::book::Book;
+use super::client::Bitso;
+use super::error::{required, BitsoError, Result};
+use super::model::public::AvailableBook;
+use super::model::Decimal;
+use super::precision::Precision;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Instant;
+
+/// Entry left out of a [`BookRegistry`], with the reason it was skipped
+pub type SkippedBook = (Option<Book>, BitsoError);
+
+/// Trading limits of a book, as listed by Bitso.
+///
+/// For more info, see: <https://bitso.com/api_info#available-books>
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookLimits {
+    pub book: Book,
+    pub minimum_amount: Decimal,
+    pub maximum_amount: Decimal,
+    pub minimum_price: Decimal,
+    pub maximum_price: Decimal,
+    pub minimum_value: Decimal,
+    pub maximum_value: Decimal,
+    pub precision: Precision,
+}
+
+impl BookLimits {
+    fn from_available_book(available: &AvailableBook) -> Result<BookLimits> {
+        let book = available
+            .book
+            .clone()
+            .ok_or_else(|| BitsoError::Validation("available book without a name".to_owned()))?;
+        Ok(BookLimits {
+            minimum_amount: required("minimum_amount", available.minimum_amount)?,
+            maximum_amount: required("maximum_amount", available.maximum_amount)?,
+            minimum_price: required("minimum_price", available.minimum_price)?,
+            maximum_price: required("maximum_price", available.maximum_price)?,
+            minimum_value: required("minimum_value", available.minimum_value)?,
+            maximum_value: required("maximum_value", available.maximum_value)?,
+            precision: Precision::from_available_book(available),
+            book,
+        })
+    }
+}
+
+/// Books currently listed by Bitso, loaded from
+/// [`get_available_books`](Bitso::get_available_books).
+///
+/// Unlike [`Book::KNOWN`], the registry follows the listing at runtime, so
+/// books added after this crate was released can be discovered and
+/// validated. Call [`refresh`](Self::refresh) to pick up later changes.
+///
+/// Entries listed without a name or without some limit are left out and
+/// reported by [`skipped`](Self::skipped) instead of failing the load.
+///
+/// # Examples
+/// ```no_run
+/// use bitsors::book::Book;
+/// use bitsors::client::Bitso;
+/// use bitsors::registry::BookRegistry;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let registry = BookRegistry::load(Bitso::default()).await.unwrap();
+/// let limits = registry.validate(&Book::BtcMxn).unwrap();
+/// println!("Minimum amount: {}", limits.minimum_amount);
+///
+/// for limits in registry.by_quote("mxn") {
+///     println!("{}", limits.book);
+/// }
+/// # }
+/// ```
+pub struct BookRegistry {
+    client: Bitso,
+    books: HashMap<Book, BookLimits>,
+    skipped: Vec<SkippedBook>,
+    refreshed_at: Instant,
+}
+
+impl fmt::Debug for BookRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BookRegistry")
+            .field("books", &self.books.len())
+            .field("skipped", &self.skipped)
+            .field("refreshed_at", &self.refreshed_at)
+            .finish()
+    }
+}
+
+impl BookRegistry {
+    /// Fetches the available books and builds a registry out of them.
+    pub async fn load(client: Bitso) -> Result<BookRegistry> {
+        let available = client.get_available_books().await?.payload;
+        Ok(BookRegistry::from_available_books(client, &available))
+    }
+
+    /// Builds a registry out of an available books response. `client` is
+    /// used by [`refresh`](Self::refresh).
+    pub fn from_available_books(client: Bitso, available: &[AvailableBook]) -> BookRegistry {
+        let (books, skipped) = index(available);
+        BookRegistry {
+            client,
+            books,
+            skipped,
+            refreshed_at: Instant::now(),
+        }
+    }
+
+    /// Fetches the available books again. The registry is left untouched
+    /// if the request fails.
+    pub async fn refresh(&mut self) -> Result<()> {
+        let available = self.client.get_available_books().await?.payload;
+        let (books, skipped) = index(&available);
+        self.books = books;
+        self.skipped = skipped;
+        self.refreshed_at = Instant::now();
+        Ok(())
+    }
+
+    /// When the books were last loaded
+    pub fn refreshed_at(&self) -> Instant {
+        self.refreshed_at
+    }
+
+    /// Entries of the last load that were left out, with the reason
+    pub fn skipped(&self) -> &[SkippedBook] {
+        &self.skipped
+    }
+
+    /// Limits of `book`, if it is listed
+    pub fn get(&self, book: &Book) -> Option<&BookLimits> {
+        self.books.get(book)
+    }
+
+    /// Limits of the book named e.g. `btc_mxn`, if it is listed
+    pub fn by_name(&self, name: &str) -> Option<&BookLimits> {
+        self.get(&Book::from(name))
+    }
+
+    /// Limits of the book trading `base` against `quote`, if it is listed
+    pub fn by_pair(&self, base: &str, quote: &str) -> Option<&BookLimits> {
+        self.get(&Book::new(base, quote))
+    }
+
+    /// Every listed book with `quote` as its quote currency
+    pub fn by_quote<'a>(&'a self, quote: &'a str) -> impl Iterator<Item = &'a BookLimits> {
+        self.books
+            .values()
+            .filter(move |limits| limits.book.quote().eq_ignore_ascii_case(quote))
+    }
+
+    /// Whether `book` is listed
+    pub fn contains(&self, book: &Book) -> bool {
+        self.books.contains_key(book)
+    }
+
+    /// Limits of `book`, or a validation error if it is not listed
+    pub fn validate(&self, book: &Book) -> Result<&BookLimits> {
+        self.get(book)
+            .ok_or_else(|| BitsoError::Validation(format!("book {} is not available", book)))
+    }
+
+    /// Every listed book, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &BookLimits> {
+        self.books.values()
+    }
+
+    /// Number of listed books
+    pub fn len(&self) -> usize {
+        self.books.len()
+    }
+
+    /// Whether no book is listed
+    pub fn is_empty(&self) -> bool {
+        self.books.is_empty()
+    }
+}
+
+fn index(available: &[AvailableBook]) -> (HashMap<Book, BookLimits>, Vec<SkippedBook>) {
+    let mut books = HashMap::new();
+    let mut skipped = Vec::new();
+    for available in available {
+        match BookLimits::from_available_book(available) {
+            Ok(limits) => {
+                books.insert(limits.book.clone(), limits);
+            }
+            Err(error) => skipped.push((available.book.clone(), error)),
+        }
+    }
+    (books, skipped)
+}
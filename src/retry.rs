@@ -0,0 +1,172 @@
+use super::error::{BitsoError, ErrorCode};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Method, StatusCode};
+use std::time::Duration;
+
+/// Retry policy used by [`Bitso`](crate::client::Bitso) when a request fails.
+///
+/// Only idempotent requests are retried by default. `POST` requests, such as
+/// `place_order` or the withdrawal calls, are sent exactly once unless
+/// [`RetryPolicy::retry_non_idempotent`] is enabled.
+///
+/// # Examples
+/// ```
+/// use bitsors::client::Bitso;
+/// use bitsors::retry::RetryPolicy;
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::default()
+///     .max_attempts(5)
+///     .base_delay(Duration::from_millis(100))
+///     .build();
+/// let bitso = Bitso::default().retry_policy(policy).build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: bool,
+    pub retry_statuses: Vec<StatusCode>,
+    pub retry_codes: Vec<ErrorCode>,
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    /// Retry up to three times on rate limits, server errors
+    /// and retryable Bitso error codes.
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            retry_statuses: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_codes: vec![
+                ErrorCode::UnknownError,
+                ErrorCode::InvalidNonce,
+                ErrorCode::TooManyRequests,
+            ],
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that sends every request exactly once.
+    pub fn none() -> RetryPolicy {
+        RetryPolicy::default().max_attempts(1)
+    }
+
+    /// Set the maximum number of attempts, including the first one
+    pub fn max_attempts(mut self, max_attempts: u32) -> RetryPolicy {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the delay before the first retry
+    pub fn base_delay(mut self, base_delay: Duration) -> RetryPolicy {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Set the upper bound for the backoff delay, which also caps
+    /// delays requested through `Retry-After`
+    pub fn max_delay(mut self, max_delay: Duration) -> RetryPolicy {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Enable or disable random jitter on the backoff delay
+    pub fn jitter(mut self, jitter: bool) -> RetryPolicy {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set the HTTP statuses that are retried
+    pub fn retry_statuses(mut self, retry_statuses: Vec<StatusCode>) -> RetryPolicy {
+        self.retry_statuses = retry_statuses;
+        self
+    }
+
+    /// Set the Bitso error codes that are retried
+    pub fn retry_codes(mut self, retry_codes: Vec<ErrorCode>) -> RetryPolicy {
+        self.retry_codes = retry_codes;
+        self
+    }
+
+    /// Allow retrying non-idempotent requests such as orders and withdrawals
+    pub fn retry_non_idempotent(mut self, retry_non_idempotent: bool) -> RetryPolicy {
+        self.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    /// Build RetryPolicy object
+    pub fn build(self) -> RetryPolicy {
+        self
+    }
+
+    /// Whether a request that failed with `error` on attempt number
+    /// `attempt` (starting at 1) should be sent again.
+    pub fn should_retry(&self, method: &Method, error: &BitsoError, attempt: u32) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        if !self.retry_non_idempotent && !is_idempotent(method) {
+            return false;
+        }
+        match error {
+            BitsoError::Transport(e) => e.is_timeout() || e.is_connect(),
+            BitsoError::Http { status, .. } => self.retry_statuses.contains(status),
+            BitsoError::Api { status, error } => {
+                matches!(error.code(), Some(code) if self.retry_codes.contains(code))
+                    || self.retry_statuses.contains(status)
+            }
+            _ => false,
+        }
+    }
+
+    /// Exponential backoff delay to wait after attempt number `attempt`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .checked_mul(1 << exponent)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+        if self.jitter && delay > Duration::from_millis(0) {
+            // "Equal jitter": keep half of the delay and randomize the rest.
+            let half = delay / 2;
+            half + rand::thread_rng().gen_range(Duration::from_millis(0)..=half)
+        } else {
+            delay
+        }
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+    )
+}
+
+/// Delay requested by the server through the `Retry-After` header, either
+/// in seconds or as an HTTP date. Dates in the past mean no delay.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = at.with_timezone(&Utc) - Utc::now();
+    Some(delay.to_std().unwrap_or_else(|_| Duration::from_secs(0)))
+}
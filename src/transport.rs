@@ -0,0 +1,229 @@
+use super::error::Result;
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Method, StatusCode, Url};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+/// An HTTP request produced by [`Bitso`](crate::client::Bitso), already signed.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    /// Absolute URL, including the query string.
+    pub url: String,
+    pub headers: HeaderMap,
+    /// JSON body, only set for POST requests.
+    pub body: Option<Value>,
+}
+
+impl HttpRequest {
+    /// Path of the request, without the query string.
+    pub fn path(&self) -> String {
+        match Url::parse(&self.url) {
+            Ok(url) => url.path().to_owned(),
+            Err(_) => self.url.split('?').next().unwrap_or_default().to_owned(),
+        }
+    }
+
+    /// Value of a query parameter, if present.
+    pub fn query_param(&self, name: &str) -> Option<String> {
+        let url = Url::parse(&self.url).ok()?;
+        let value = url
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned());
+        value
+    }
+}
+
+/// An HTTP response handed back to [`Bitso`](crate::client::Bitso) by a [`Transport`].
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// Response with the given status and body, and no headers.
+    pub fn new(status: StatusCode, body: &str) -> HttpResponse {
+        HttpResponse {
+            status,
+            headers: HeaderMap::new(),
+            body: body.to_owned(),
+        }
+    }
+}
+
+/// The HTTP layer used by [`Bitso`](crate::client::Bitso) to send requests.
+///
+/// Non-success statuses must be returned as an [`HttpResponse`]; errors are
+/// reserved for requests that could not be sent or read at all.
+#[async_trait]
+pub trait Transport: Debug + Send + Sync {
+    /// Sends a request and returns Bitso's response.
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+}
+
+/// Default [`Transport`], backed by a `reqwest` client.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    /// Transport sending requests through the given client
+    pub fn new(client: Client) -> ReqwestTransport {
+        ReqwestTransport { client }
+    }
+}
+
+impl From<Client> for ReqwestTransport {
+    fn from(client: Client) -> Self {
+        ReqwestTransport::new(client)
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let mut builder = self
+            .client
+            .request(request.method, &request.url)
+            .headers(request.headers);
+        if let Some(json) = &request.body {
+            builder = builder.json(json);
+        }
+        let response = builder.send().await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.text().await?;
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Route {
+    method: Method,
+    path: String,
+    responses: VecDeque<HttpResponse>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    routes: Vec<Route>,
+    requests: Vec<HttpRequest>,
+}
+
+/// In-memory [`Transport`] that answers with canned responses and records
+/// every request it receives. Useful to test code built on top of
+/// [`Bitso`](crate::client::Bitso) without opening sockets.
+///
+/// Responses are matched by method and path. When several responses are
+/// registered for the same route they are returned in order, and the last
+/// one keeps being returned once the others are used up. Requests without a
+/// canned response get a `404 Not Found`.
+///
+/// Clones share the same responses and recorded requests.
+///
+/// # Examples
+/// ```
+/// use bitsors::client::Bitso;
+/// use bitsors::transport::MemoryTransport;
+/// use reqwest::{Method, StatusCode};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let transport = MemoryTransport::new();
+/// transport.respond(
+///     Method::GET,
+///     "/v3/available_books/",
+///     StatusCode::OK,
+///     r#"{"success": true, "payload": []}"#,
+/// );
+/// let bitso = Bitso::default().transport(transport.clone()).build();
+/// let books = bitso.get_available_books().await.unwrap();
+/// assert!(books.payload.is_empty());
+/// assert_eq!(transport.requests().len(), 1);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MemoryTransport {
+    state: Arc<Mutex<MemoryState>>,
+}
+
+impl MemoryTransport {
+    /// Transport without any canned response
+    pub fn new() -> MemoryTransport {
+        MemoryTransport::default()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Registers a response with the given status and body for a route
+    pub fn respond(&self, method: Method, path: &str, status: StatusCode, body: &str) {
+        self.respond_with(method, path, HttpResponse::new(status, body));
+    }
+
+    /// Registers a full response for a route
+    pub fn respond_with(&self, method: Method, path: &str, response: HttpResponse) {
+        let mut state = self.state();
+        match state
+            .routes
+            .iter_mut()
+            .find(|r| r.method == method && r.path == path)
+        {
+            Some(route) => route.responses.push_back(response),
+            None => state.routes.push(Route {
+                method,
+                path: path.to_owned(),
+                responses: vec![response].into(),
+            }),
+        }
+    }
+
+    /// Every request received so far, oldest first
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.state().requests.clone()
+    }
+
+    /// Forgets recorded requests, keeping the canned responses
+    pub fn clear_requests(&self) {
+        self.state().requests.clear();
+    }
+}
+
+#[async_trait]
+impl Transport for MemoryTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let path = request.path();
+        let mut state = self.state();
+        let response = state
+            .routes
+            .iter_mut()
+            .find(|r| r.method == request.method && r.path == path)
+            .and_then(|route| {
+                if route.responses.len() > 1 {
+                    route.responses.pop_front()
+                } else {
+                    route.responses.front().cloned()
+                }
+            })
+            .unwrap_or_else(|| {
+                HttpResponse::new(
+                    StatusCode::NOT_FOUND,
+                    &format!("No canned response for {} {}", request.method, path),
+                )
+            });
+        state.requests.push(request);
+        Ok(response)
+    }
+}
@@ -1,16 +1,32 @@
+use super::error::{BitsoError, Result};
 use super::model::websocket::*;
+use super::recording::{FrameKind, RecordedFrame, Recorder};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
 use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
 
+pub use super::book::Book;
 pub use strum::{EnumCount, IntoEnumIterator};
 
+use native_tls::TlsConnector;
+use reqwest::header::HeaderMap;
 use strum_macros::{AsRefStr, Display, EnumCount, EnumIter, EnumString};
-use tungstenite::{client::AutoStream, connect, error::Result, Message, WebSocket};
+use tungstenite::client::{client_with_config, uri_mode, AutoStream, IntoClientRequest};
+use tungstenite::error::{TlsError, UrlError};
+use tungstenite::handshake::HandshakeError;
+use tungstenite::stream::{Mode, Stream as StreamSwitcher};
+use tungstenite::{Message, WebSocket};
 
 /// Bitso WebSocket object.
 ///
 /// For more info see: <https://bitso.com/api_info?#websocket-api>
 ///
-/// Check all the possible options in [`Books`] and [`Subscription`]
+/// Check all the possible options in [`Book`] and [`Subscription`]
 ///
 /// # Examples
 /// ```no_run
@@ -19,12 +35,12 @@ use tungstenite::{client::AutoStream, connect, error::Result, Message, WebSocket
 /// let mut socket = BitsoWebSocket::new().unwrap();
 ///
 /// // You can subscribe to a specific orders channel
-/// socket.subscribe(Subscription::Orders, Books::BtcMxn).unwrap();
+/// socket.subscribe(Subscription::Orders, Book::BtcMxn).unwrap();
 ///                                                         
-/// // You can also iterate over all the Books and Subscription channels
-/// for book in Books::iter() {
+/// // You can also iterate over all the Book and Subscription channels
+/// for book in Book::iter() {
 ///     for subs in Subscription::iter() {
-///         socket.subscribe(subs, book).unwrap();
+///         socket.subscribe(subs, book.clone()).unwrap();
 ///     }
 /// }
 ///
@@ -33,6 +49,9 @@ use tungstenite::{client::AutoStream, connect, error::Result, Message, WebSocket
 ///         Response::Orders(r) => println!("{:?}", r),
 ///         Response::Trades(r) => println!("{:?}", r),
 ///         Response::DiffOrders(r) => println!("{:?}", r),
+///         Response::Ack(r) => println!("{:?}", r),
+///         Response::Reconnected { .. } => println!("Reconnected, data may have been missed"),
+///         Response::Unknown(v) => println!("Unknown message: {}", v),
 ///     }
 /// }
 /// ```
@@ -40,50 +59,628 @@ use tungstenite::{client::AutoStream, connect, error::Result, Message, WebSocket
 #[derive(Debug)]
 pub struct BitsoWebSocket {
     socket: WebSocket<AutoStream>,
+    config: WebSocketConfig,
+    subscriptions: Vec<(Subscription, Book)>,
+    pending: VecDeque<Response>,
+    reconnect_policy: Option<ReconnectPolicy>,
+    stale_timeout: Option<Duration>,
+    ack_timeout: Duration,
+    connected_at: Instant,
+    last_keep_alive: Option<Instant>,
+    last_message: Option<Instant>,
+    stale_reported_at: Option<Instant>,
+    recorder: Option<Recorder>,
+    closed: bool,
 }
 
 impl BitsoWebSocket {
     /// Creates a new WebSocket connection.
     pub fn new() -> Result<Self> {
-        let (socket, _) = connect("wss://ws.bitso.com")?;
-        Ok(BitsoWebSocket { socket })
+        BitsoWebSocket::with_config(WebSocketConfig::default())
+    }
+
+    /// Creates a new WebSocket connection to the given URL.
+    pub fn connect(url: &str) -> Result<Self> {
+        BitsoWebSocket::with_config(WebSocketConfig::default().url(url))
+    }
+
+    /// Creates a new WebSocket connection with the given settings.
+    pub fn with_config(config: WebSocketConfig) -> Result<Self> {
+        let socket = dial(&config)?;
+        Ok(BitsoWebSocket {
+            socket,
+            config,
+            subscriptions: Vec::new(),
+            pending: VecDeque::new(),
+            reconnect_policy: None,
+            stale_timeout: None,
+            ack_timeout: Duration::from_secs(10),
+            connected_at: Instant::now(),
+            last_keep_alive: None,
+            last_message: None,
+            stale_reported_at: None,
+            recorder: None,
+            closed: false,
+        })
+    }
+
+    /// Enables automatic reconnection. When the connection drops, [`read`](Self::read)
+    /// redials following `policy`, subscribes again to every channel passed to
+    /// [`subscribe`](Self::subscribe) and returns [`Response::Reconnected`].
+    pub fn reconnect_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = Some(policy);
+        self
+    }
+
+    /// Enables stale connection detection. When neither a keep-alive nor a
+    /// channel message arrives within `timeout`, [`read`](Self::read) returns
+    /// [`BitsoError::Stale`], and keeps returning it once per `timeout` while
+    /// the connection stays silent.
+    pub fn stale_timeout(mut self, timeout: Duration) -> Self {
+        self.stale_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how long [`subscribe`](Self::subscribe) waits for Bitso's
+    /// acknowledgement before failing with [`BitsoError::AckTimeout`].
+    /// Defaults to ten seconds.
+    pub fn ack_timeout(mut self, timeout: Duration) -> Self {
+        self.ack_timeout = timeout;
+        self
+    }
+
+    /// Records every frame received from now on to a JSONL file at `path`,
+    /// which can be played back with [`Replay`](crate::recording::Replay).
+    pub fn record<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
+        self.recorder = Some(Recorder::create(path)?);
+        Ok(self)
+    }
+
+    /// Records every frame received from now on to `writer`, one JSON line
+    /// per frame.
+    pub fn record_to<W: Write + Send + 'static>(mut self, writer: W) -> Self {
+        self.recorder = Some(Recorder::new(writer));
+        self
+    }
+
+    /// When the last keep-alive message arrived.
+    pub fn last_keep_alive(&self) -> Option<Instant> {
+        self.last_keep_alive
+    }
+
+    /// When the last message other than a keep-alive arrived.
+    pub fn last_message(&self) -> Option<Instant> {
+        self.last_message
+    }
+
+    /// Time since the connection last showed signs of life: the last
+    /// keep-alive, the last message, or the connection itself.
+    pub fn idle(&self) -> Duration {
+        self.last_seen().elapsed()
+    }
+
+    fn last_seen(&self) -> Instant {
+        self.last_keep_alive
+            .into_iter()
+            .chain(self.last_message)
+            .fold(self.connected_at, Instant::max)
     }
 
     /// Closes an existing WebSocket connection.
     pub fn close(&mut self) -> Result<()> {
-        self.socket.close(None)
+        self.closed = true;
+        Ok(self.socket.close(None)?)
+    }
+
+    /// Subscribes to a given channel and waits for Bitso's acknowledgement.
+    ///
+    /// Channel messages received before the acknowledgement are kept and
+    /// returned by the following calls to [`read`](Self::read). Fails with
+    /// [`BitsoError::AckTimeout`] if no acknowledgement arrives within the
+    /// [ack timeout](Self::ack_timeout).
+    pub fn subscribe(
+        &mut self,
+        subscription_type: Subscription,
+        book: Book,
+    ) -> Result<SubscriptionAck> {
+        let request = subscription_request("subscribe", subscription_type, &book);
+        self.socket.write_message(Message::Text(request))?;
+        let deadline = Instant::now() + self.ack_timeout;
+        let result = self.wait_for_ack(subscription_type, book, deadline);
+        if self.stale_timeout.is_none() {
+            self.set_read_timeout(None)?;
+        }
+        result
+    }
+
+    fn wait_for_ack(
+        &mut self,
+        subscription_type: Subscription,
+        book: Book,
+        deadline: Instant,
+    ) -> Result<SubscriptionAck> {
+        loop {
+            match self.read_response(Some(deadline))? {
+                Response::Ack(mut ack)
+                    if is_ack_for(&ack, "subscribe", subscription_type, &book) =>
+                {
+                    ack.book.get_or_insert_with(|| book.clone());
+                    let subscription = (subscription_type, book);
+                    if ack.is_ok() && !self.subscriptions.contains(&subscription) {
+                        self.subscriptions.push(subscription);
+                    }
+                    return Ok(ack);
+                }
+                response => self.pending.push_back(response),
+            }
+        }
     }
 
-    /// Creates a subscription request to a given channel.
-    pub fn subscribe(&mut self, subscription_type: Subscription, book: Books) -> Result<String> {
-        let request = format!(
-            r#"{{"action":"subscribe","book":"{}","type":"{}"}}"#,
-            book.as_ref(),
-            subscription_type.as_ref()
-        );
+    /// Removes a subscription to a given channel. Bitso's acknowledgement,
+    /// if any, is returned by [`read`](Self::read) as [`Response::Ack`].
+    pub fn unsubscribe(&mut self, subscription_type: Subscription, book: Book) -> Result<()> {
+        let request = subscription_request("unsubscribe", subscription_type, &book);
         self.socket.write_message(Message::Text(request))?;
-        self.socket.read_message()?.into_text()
+        self.subscriptions
+            .retain(|(s, b)| (*s, b) != (subscription_type, &book));
+        Ok(())
+    }
+
+    /// Channels this connection is currently subscribed to.
+    pub fn subscriptions(&self) -> &[(Subscription, Book)] {
+        &self.subscriptions
     }
 
     /// Reads the response from the WebSocket connection.
     pub fn read(&mut self) -> Result<Response> {
-        let mut data = self.socket.read_message()?.into_text()?;
-        while data.contains(r#""type":"ka""#) || data.contains("subscribe") {
-            //ignore keep alive and subscribe messages
-            data = self.socket.read_message()?.into_text()?;
-        }
-        let first_comma = data.find(':').unwrap();
-        let second_comma = data[first_comma + 1..].find(',').unwrap() + first_comma;
-        match Subscription::from_str(&data[first_comma + 2..second_comma]).unwrap() {
-            Subscription::Trades => Ok(Response::Trades(serde_json::from_str(&data).unwrap())),
-            Subscription::DiffOrders => {
-                Ok(Response::DiffOrders(serde_json::from_str(&data).unwrap()))
+        if let Some(response) = self.pending.pop_front() {
+            return Ok(response);
+        }
+        match (self.read_response(None), self.reconnect_policy) {
+            (Err(error @ BitsoError::WebSocket(_)), Some(policy)) if !self.closed => {
+                self.reconnect(policy, error)
+            }
+            (result, _) => result,
+        }
+    }
+
+    /// Reads frames until one of them holds a [`Response`], giving up
+    /// with [`BitsoError::AckTimeout`] once `deadline` passes.
+    fn read_response(&mut self, deadline: Option<Instant>) -> Result<Response> {
+        loop {
+            let mut read_timeout = None;
+            if let Some(timeout) = self.stale_timeout {
+                // A silent connection is reported once per timeout
+                // instead of on every call.
+                let waited = self
+                    .stale_reported_at
+                    .map_or(self.last_seen(), |at| at.max(self.last_seen()))
+                    .elapsed();
+                if waited >= timeout {
+                    self.stale_reported_at = Some(Instant::now());
+                    return Err(BitsoError::Stale { since: self.idle() });
+                }
+                read_timeout = Some(timeout - waited);
+            }
+            if let Some(deadline) = deadline {
+                let left = deadline.saturating_duration_since(Instant::now());
+                if left == Duration::from_secs(0) {
+                    return Err(BitsoError::AckTimeout {
+                        timeout: self.ack_timeout,
+                    });
+                }
+                read_timeout = Some(read_timeout.map_or(left, |t: Duration| t.min(left)));
+            }
+            if read_timeout.is_some() {
+                self.set_read_timeout(read_timeout)?;
+            }
+            let message = match self.socket.read_message() {
+                Ok(message) => message,
+                Err(tungstenite::Error::Io(e))
+                    if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut =>
+                {
+                    continue
+                }
+                Err(e) => return Err(e.into()),
+            };
+            match message {
+                Message::Close(frame) => {
+                    let reason = frame.map(|f| f.reason.into_owned().into_bytes());
+                    self.record_control(FrameKind::Close, reason.unwrap_or_default())?;
+                    return Err(tungstenite::Error::ConnectionClosed.into());
+                }
+                Message::Ping(data) => self.record_control(FrameKind::Ping, data)?,
+                Message::Pong(data) => self.record_control(FrameKind::Pong, data)?,
+                message => match self.parse(message.into_data())? {
+                    Some(response) => {
+                        self.last_message = Some(Instant::now());
+                        return Ok(response);
+                    }
+                    // Keep alive
+                    None => self.last_keep_alive = Some(Instant::now()),
+                },
+            }
+        }
+    }
+
+    /// Records a control frame if needed.
+    fn record_control(&mut self, kind: FrameKind, data: Vec<u8>) -> Result<()> {
+        match &mut self.recorder {
+            Some(recorder) => recorder.record(&RecordedFrame::control(kind, data)),
+            None => Ok(()),
+        }
+    }
+
+    /// Records the frame if needed, then parses it.
+    fn parse(&mut self, data: Vec<u8>) -> Result<Option<Response>> {
+        match &mut self.recorder {
+            Some(recorder) => {
+                let frame = RecordedFrame::new(data);
+                recorder.record(&frame)?;
+                parse_message(frame.data())
             }
-            Subscription::Orders => Ok(Response::Orders(serde_json::from_str(&data).unwrap())),
+            None => parse_message(&data),
+        }
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        tcp_stream(self.socket.get_ref())
+            .set_read_timeout(timeout)
+            .map_err(|e| tungstenite::Error::Io(e).into())
+    }
+
+    /// Redials and subscribes again to every known channel.
+    fn reconnect(&mut self, policy: ReconnectPolicy, mut error: BitsoError) -> Result<Response> {
+        let mut attempt = 1;
+        while policy.max_attempts.map_or(true, |max| attempt <= max) {
+            thread::sleep(policy.backoff(attempt));
+            match self.resubscribe() {
+                Ok(()) => return Ok(Response::Reconnected { attempts: attempt }),
+                Err(e) => error = e,
+            }
+            attempt += 1;
+        }
+        Err(error)
+    }
+
+    fn resubscribe(&mut self) -> Result<()> {
+        self.socket = dial(&self.config)?;
+        self.connected_at = Instant::now();
+        self.stale_reported_at = None;
+        for (subscription_type, book) in &self.subscriptions {
+            let request = subscription_request("subscribe", *subscription_type, book);
+            self.socket.write_message(Message::Text(request))?;
+        }
+        Ok(())
+    }
+}
+
+/// A source of [`Response`]s: either a live [`BitsoWebSocket`] or a
+/// recording played back by [`Replay`](crate::recording::Replay).
+pub trait ResponseSource {
+    /// Subscribes to a given channel.
+    fn subscribe(&mut self, subscription_type: Subscription, book: Book)
+        -> Result<SubscriptionAck>;
+
+    /// Reads the next response.
+    fn read(&mut self) -> Result<Response>;
+
+    /// Closes the source.
+    fn close(&mut self) -> Result<()>;
+
+    /// Whether reading can go on after [`read`](Self::read) returned
+    /// `error`. Only [`BitsoError::Stale`] by default.
+    fn is_recoverable(&self, error: &BitsoError) -> bool {
+        matches!(error, BitsoError::Stale { .. })
+    }
+}
+
+impl ResponseSource for BitsoWebSocket {
+    fn subscribe(
+        &mut self,
+        subscription_type: Subscription,
+        book: Book,
+    ) -> Result<SubscriptionAck> {
+        BitsoWebSocket::subscribe(self, subscription_type, book)
+    }
+
+    fn read(&mut self) -> Result<Response> {
+        BitsoWebSocket::read(self)
+    }
+
+    fn close(&mut self) -> Result<()> {
+        BitsoWebSocket::close(self)
+    }
+
+    /// Stale connections, and dropped ones when a [`ReconnectPolicy`] is
+    /// set, are recoverable: the next read redials.
+    fn is_recoverable(&self, error: &BitsoError) -> bool {
+        match error {
+            BitsoError::Stale { .. } => true,
+            BitsoError::WebSocket(_) => self.reconnect_policy.is_some() && !self.closed,
+            _ => false,
         }
     }
 }
 
+/// Settings used by [`BitsoWebSocket`] to connect, and to redial after the
+/// connection drops.
+///
+/// # Examples
+/// ```no_run
+/// use bitsors::websocket::*;
+/// use std::time::Duration;
+///
+/// let config = WebSocketConfig::default()
+///     .url("wss://ws-dev.bitso.com")
+///     .connect_timeout(Duration::from_secs(5))
+///     .build();
+/// let socket = BitsoWebSocket::with_config(config).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct WebSocketConfig {
+    pub url: String,
+    pub connect_timeout: Option<Duration>,
+    /// Connector used for `wss://` URLs. A default one is built when unset.
+    pub tls_connector: Option<TlsConnector>,
+    pub headers: HeaderMap,
+    pub max_frame_size: Option<usize>,
+    pub max_message_size: Option<usize>,
+}
+
+impl Default for WebSocketConfig {
+    /// Bitso's production endpoint with `tungstenite`'s default limits.
+    fn default() -> WebSocketConfig {
+        let limits = tungstenite::protocol::WebSocketConfig::default();
+        WebSocketConfig {
+            url: "wss://ws.bitso.com".to_owned(),
+            connect_timeout: None,
+            tls_connector: None,
+            headers: HeaderMap::new(),
+            max_frame_size: limits.max_frame_size,
+            max_message_size: limits.max_message_size,
+        }
+    }
+}
+
+impl WebSocketConfig {
+    /// Set the URL to connect to
+    pub fn url(mut self, url: &str) -> WebSocketConfig {
+        self.url = url.to_owned();
+        self
+    }
+
+    /// Set the timeout for establishing a connection, including the
+    /// TLS and WebSocket handshakes
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> WebSocketConfig {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Set the TLS connector used for `wss://` URLs
+    pub fn tls_connector(mut self, tls_connector: TlsConnector) -> WebSocketConfig {
+        self.tls_connector = Some(tls_connector);
+        self
+    }
+
+    /// Set headers added to the handshake request
+    pub fn headers(mut self, headers: HeaderMap) -> WebSocketConfig {
+        self.headers = headers;
+        self
+    }
+
+    /// Set the maximum size of a single frame. `None` means no limit.
+    pub fn max_frame_size(mut self, max_frame_size: Option<usize>) -> WebSocketConfig {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Set the maximum size of a message. `None` means no limit.
+    pub fn max_message_size(mut self, max_message_size: Option<usize>) -> WebSocketConfig {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// Build WebSocketConfig object
+    pub fn build(self) -> WebSocketConfig {
+        self
+    }
+}
+
+/// Opens a connection and does the TLS and WebSocket handshakes.
+fn dial(config: &WebSocketConfig) -> Result<WebSocket<AutoStream>> {
+    let io = |e: io::Error| BitsoError::from(tungstenite::Error::Io(e));
+    let mut request = config.url.as_str().into_client_request()?;
+    request.headers_mut().extend(config.headers.clone());
+    let uri = request.uri();
+    let mode = uri_mode(uri)?;
+    let host = uri
+        .host()
+        .ok_or(tungstenite::Error::Url(UrlError::NoHostName))?
+        .to_owned();
+    let port = uri.port_u16().unwrap_or(match mode {
+        Mode::Plain => 80,
+        Mode::Tls => 443,
+    });
+
+    let mut result = Err(io::Error::new(
+        ErrorKind::NotFound,
+        format!("{} did not resolve to any address", host),
+    ));
+    for addr in (host.as_str(), port).to_socket_addrs().map_err(io)? {
+        result = match config.connect_timeout {
+            Some(timeout) => TcpStream::connect_timeout(&addr, timeout),
+            None => TcpStream::connect(addr),
+        };
+        if result.is_ok() {
+            break;
+        }
+    }
+    let stream = result.map_err(io)?;
+    stream.set_nodelay(true).map_err(io)?;
+    stream
+        .set_read_timeout(config.connect_timeout)
+        .map_err(io)?;
+    stream
+        .set_write_timeout(config.connect_timeout)
+        .map_err(io)?;
+
+    let stream = match mode {
+        Mode::Plain => StreamSwitcher::Plain(stream),
+        Mode::Tls => {
+            let connector = match &config.tls_connector {
+                Some(connector) => connector.clone(),
+                None => TlsConnector::new()
+                    .map_err(|e| tungstenite::Error::from(TlsError::Native(e)))?,
+            };
+            let stream = connector.connect(&host, stream).map_err(|e| match e {
+                native_tls::HandshakeError::Failure(e) => {
+                    tungstenite::Error::from(TlsError::Native(e)).into()
+                }
+                native_tls::HandshakeError::WouldBlock(_) => io(ErrorKind::TimedOut.into()),
+            })?;
+            StreamSwitcher::Tls(stream)
+        }
+    };
+
+    let limits = tungstenite::protocol::WebSocketConfig {
+        max_frame_size: config.max_frame_size,
+        max_message_size: config.max_message_size,
+        ..Default::default()
+    };
+    let (socket, _) = client_with_config(request, stream, Some(limits)).map_err(|e| match e {
+        HandshakeError::Failure(e) => e.into(),
+        HandshakeError::Interrupted(_) => io(ErrorKind::TimedOut.into()),
+    })?;
+    let stream = tcp_stream(socket.get_ref());
+    stream.set_read_timeout(None).map_err(io)?;
+    stream.set_write_timeout(None).map_err(io)?;
+    Ok(socket)
+}
+
+fn tcp_stream(stream: &AutoStream) -> &TcpStream {
+    match stream {
+        StreamSwitcher::Plain(stream) => stream,
+        StreamSwitcher::Tls(stream) => stream.get_ref(),
+    }
+}
+
+/// How [`BitsoWebSocket`] redials after the connection drops.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    /// Maximum number of redials. `None` keeps trying forever.
+    pub max_attempts: Option<u32>,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    /// Redial forever, waiting from half a second up to 30 seconds between attempts.
+    fn default() -> ReconnectPolicy {
+        ReconnectPolicy {
+            max_attempts: None,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Set the maximum number of redials
+    pub fn max_attempts(mut self, max_attempts: Option<u32>) -> ReconnectPolicy {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set the delay before the first redial
+    pub fn base_delay(mut self, base_delay: Duration) -> ReconnectPolicy {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Set the upper bound for the delay between redials
+    pub fn max_delay(mut self, max_delay: Duration) -> ReconnectPolicy {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Build ReconnectPolicy object
+    pub fn build(self) -> ReconnectPolicy {
+        self
+    }
+
+    /// Exponential backoff delay to wait before redial number `attempt`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        self.base_delay
+            .checked_mul(1 << exponent)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+}
+
+/// Builds the JSON request for a subscription `action` on a given channel.
+pub(crate) fn subscription_request(
+    action: &str,
+    subscription_type: Subscription,
+    book: &Book,
+) -> String {
+    format!(
+        r#"{{"action":"{}","book":"{}","type":"{}"}}"#,
+        action,
+        book.as_ref(),
+        subscription_type.as_ref()
+    )
+}
+
+/// Whether `ack` answers an `action` request for the given channel.
+pub(crate) fn is_ack_for(
+    ack: &SubscriptionAck,
+    action: &str,
+    subscription_type: Subscription,
+    book: &Book,
+) -> bool {
+    ack.action == action
+        && (ack.type_field.is_empty() || ack.type_field == subscription_type.as_ref())
+        && ack.book.as_ref().map_or(true, |b| b == book)
+}
+
+/// Parses a frame sent by Bitso into a [`Response`].
+///
+/// Keep alive messages yield `None`.
+/// Messages of an unknown type are returned as [`Response::Unknown`], and
+/// frames that are not JSON or that do not match their channel's model are
+/// returned as [`BitsoError::Decode`].
+pub fn parse_message(data: &[u8]) -> Result<Option<Response>> {
+    let decode = |source| BitsoError::Decode {
+        source,
+        body: String::from_utf8_lossy(data).into_owned(),
+    };
+    let value: Value = serde_json::from_slice(data).map_err(decode)?;
+    if value.get("action").is_some() {
+        let ack = serde_json::from_value(value).map_err(decode)?;
+        return Ok(Some(Response::Ack(ack)));
+    }
+    let subscription = match value.get("type").and_then(Value::as_str) {
+        Some("ka") => return Ok(None),
+        Some(type_field) => Subscription::from_str(type_field).ok(),
+        None => None,
+    };
+    let response = match subscription {
+        Some(Subscription::Trades) => {
+            Response::Trades(serde_json::from_value(value).map_err(decode)?)
+        }
+        Some(Subscription::DiffOrders) => {
+            Response::DiffOrders(serde_json::from_value(value).map_err(decode)?)
+        }
+        Some(Subscription::Orders) => {
+            Response::Orders(serde_json::from_value(value).map_err(decode)?)
+        }
+        None => Response::Unknown(value),
+    };
+    Ok(Some(response))
+}
+
 /// Represents the possible subscription responses in the Bitso WebSocket API.
 #[derive(Debug, Clone, PartialEq, Display, AsRefStr, EnumCount)]
 pub enum Response {
@@ -95,6 +692,16 @@ pub enum Response {
 
     ///[Orders](https://bitso.com/api_info?#orders)
     Orders(Orders),
+
+    /// Bitso's answer to a subscription request.
+    Ack(SubscriptionAck),
+
+    /// A message of a type this crate does not know about.
+    Unknown(Value),
+
+    /// The connection dropped and was dialed again after `attempts` tries.
+    /// Messages sent while disconnected were missed.
+    Reconnected { attempts: u32 },
 }
 
 /// Represents the three subscription channels in the Bitso WebSocket API.
@@ -113,58 +720,6 @@ pub enum Subscription {
     Orders,
 }
 
-/// Represents all the possible books available in Bitso.
-///
-/// For more info, see: <https://bitso.com/api_info#available-books>
-#[derive(Debug, Copy, Clone, PartialEq, Display, AsRefStr, EnumCount, EnumIter, EnumString)]
-#[non_exhaustive]
-pub enum Books {
-    #[strum(serialize = "btc_mxn")]
-    BtcMxn,
-    #[strum(serialize = "eth_btc")]
-    EthBtc,
-    #[strum(serialize = "eth_ars")]
-    EthArs,
-    #[strum(serialize = "eth_mxn")]
-    EthMxn,
-    #[strum(serialize = "xrp_btc")]
-    XrpBtc,
-    #[strum(serialize = "xrp_mxn")]
-    XrpMxn,
-    #[strum(serialize = "ltc_btc")]
-    LtcBtc,
-    #[strum(serialize = "ltc_mxn")]
-    LtcMxn,
-    #[strum(serialize = "bch_btc")]
-    BchBtc,
-    #[strum(serialize = "bch_mxn")]
-    BchMxn,
-    #[strum(serialize = "tusd_btc")]
-    TusdBtc,
-    #[strum(serialize = "tusd_mxn")]
-    TusdMxn,
-    #[strum(serialize = "mana_btc")]
-    ManaBtc,
-    #[strum(serialize = "mana_mxn")]
-    ManaMxn,
-    #[strum(serialize = "bat_btc")]
-    BatBtc,
-    #[strum(serialize = "bat_mxn")]
-    BatMxn,
-    #[strum(serialize = "btc_ars")]
-    BtcArs,
-    #[strum(serialize = "btc_dai")]
-    BtcDai,
-    #[strum(serialize = "dai_mxn")]
-    DaiMxn,
-    #[strum(serialize = "btc_usd")]
-    BtcUsd,
-    #[strum(serialize = "xrp_usd")]
-    XrpUsd,
-    #[strum(serialize = "eth_usd")]
-    EthUsd,
-    #[strum(serialize = "dai_ars")]
-    DaiArs,
-    #[strum(serialize = "btc_brl")]
-    BtcBrl,
-}
+/// Former name of [`Book`].
+#[deprecated(note = "use `Book`, which also supports books unknown to this crate")]
+pub type Books = Book;
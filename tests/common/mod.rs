@@ -0,0 +1,30 @@
+#![allow(dead_code)]
+
+use bitsors::auth::BitsoCredentials;
+use bitsors::client::Bitso;
+use bitsors::transport::MemoryTransport;
+
+/// Credentials signing the requests of the test clients
+pub fn credentials() -> BitsoCredentials {
+    BitsoCredentials::default()
+        .api_key("KEY")
+        .api_secret("SECRET")
+        .build()
+}
+
+/// Client with credentials that sends its requests to `transport`
+pub fn bitso(transport: &MemoryTransport) -> Bitso {
+    Bitso::default()
+        .client_credentials_manager(credentials())
+        .transport(transport.clone())
+        .build()
+}
+
+/// Blocking client with credentials that sends its requests to `transport`
+#[cfg(feature = "blocking")]
+pub fn blocking_bitso(transport: &MemoryTransport) -> bitsors::blocking::Bitso {
+    bitsors::blocking::Bitso::default()
+        .client_credentials_manager(credentials())
+        .transport(transport.clone())
+        .build()
+}
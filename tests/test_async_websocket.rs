@@ -0,0 +1,171 @@
+#![cfg(feature = "async-websocket")]
+extern crate bitsors;
+
+use bitsors::async_websocket::AsyncBitsoWebSocket;
+use bitsors::error::BitsoError;
+use bitsors::model::TradeId;
+use bitsors::websocket::*;
+use futures::{SinkExt, StreamExt};
+use reqwest::header::{HeaderMap, HeaderValue};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response as HttpResponse};
+use tokio_tungstenite::{accept_async, accept_hdr_async, tungstenite::Message};
+
+/// Starts a local server that acknowledges one subscription and
+/// then sends the given frames. Returns its URL.
+async fn stub_server(frames: Vec<&'static str>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = accept_async(stream).await.unwrap();
+        let request = socket.next().await.unwrap().unwrap();
+        assert!(request
+            .into_text()
+            .unwrap()
+            .contains(r#""action":"subscribe""#));
+        socket
+            .send(Message::Text(
+                r#"{"action":"subscribe","response":"ok","time":1,"type":"trades"}"#.to_owned(),
+            ))
+            .await
+            .unwrap();
+        for frame in frames {
+            socket.send(Message::Text(frame.to_owned())).await.unwrap();
+        }
+        socket.close(None).await.unwrap();
+    });
+    url
+}
+
+/// Test that channel messages are yielded by the stream
+/// and that keep alive messages are skipped
+#[tokio::test]
+async fn test_async_websocket_stream() {
+    let url = stub_server(vec![
+        r#"{"type":"ka"}"#,
+        r#"{"type":"trades","book":"btc_mxn","payload":[{"i":1,"a":"0.1","r":"500000","v":"50000","mo":"a","to":"b","t":0}]}"#,
+        r#"{"type":"diff-orders","book":"btc_mxn","sequence":2,"payload":[{"d":1,"r":"500000","t":0,"o":"abc","s":"open"}]}"#,
+    ])
+    .await;
+    let mut socket = AsyncBitsoWebSocket::connect(&url).await.unwrap();
+    let ack = socket
+        .subscribe(Subscription::Trades, Book::BtcMxn)
+        .await
+        .unwrap();
+    assert!(ack.is_ok());
+    assert_eq!(ack.book, Some(Book::BtcMxn));
+    match socket.next().await.unwrap().unwrap() {
+        Response::Trades(r) => {
+            assert_eq!(r.book, Book::BtcMxn);
+            assert_eq!(r.payload[0].i, TradeId(1));
+        }
+        r => panic!("Did not get a Response::Trades: {:?}", r),
+    }
+    match socket.next().await.unwrap().unwrap() {
+        Response::DiffOrders(r) => assert_eq!(r.sequence, 2),
+        r => panic!("Did not get a Response::DiffOrders: {:?}", r),
+    }
+    // The server closed the connection
+    assert!(socket.next().await.is_none());
+}
+
+/// Test that subscribe gives up when Bitso never acknowledges
+#[tokio::test]
+async fn test_async_websocket_ack_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = accept_async(stream).await.unwrap();
+        socket
+            .send(Message::Text(r#"{"type":"ka"}"#.to_owned()))
+            .await
+            .unwrap();
+        // Never acknowledge the subscription
+        while socket.next().await.is_some() {}
+    });
+    let timeout = Duration::from_millis(200);
+    let mut socket = AsyncBitsoWebSocket::connect(&url)
+        .await
+        .unwrap()
+        .ack_timeout(timeout);
+    match socket.subscribe(Subscription::Trades, Book::BtcMxn).await {
+        Err(BitsoError::AckTimeout { timeout: t }) => assert_eq!(t, timeout),
+        r => panic!("Expected an ack timeout, got {:?}", r),
+    }
+}
+
+/// Test that the connection honors the configured URL, headers and frame
+/// size, and keeps track of its subscriptions
+#[tokio::test]
+async fn test_async_websocket_config() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        #[allow(clippy::result_large_err)]
+        let check_headers = |request: &Request, response: HttpResponse| {
+            assert_eq!(request.headers()["x-client"], "bitsors-test");
+            Ok(response)
+        };
+        let mut socket = accept_hdr_async(stream, check_headers).await.unwrap();
+        socket.next().await.unwrap().unwrap();
+        socket
+            .send(Message::Text(
+                r#"{"action":"subscribe","response":"ok","time":1,"type":"trades"}"#.to_owned(),
+            ))
+            .await
+            .unwrap();
+        let request = socket.next().await.unwrap().unwrap();
+        assert!(request
+            .into_text()
+            .unwrap()
+            .contains(r#""action":"unsubscribe""#));
+        socket.send(Message::Text("x".repeat(1024))).await.unwrap();
+        // Keep the socket open until the client has read the frame
+        socket.next().await;
+    });
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-client", HeaderValue::from_static("bitsors-test"));
+    let config = WebSocketConfig::default()
+        .url(&url)
+        .headers(headers)
+        .connect_timeout(Duration::from_secs(5))
+        .max_frame_size(Some(512))
+        .build();
+    let mut socket = AsyncBitsoWebSocket::with_config(config).await.unwrap();
+    socket
+        .subscribe(Subscription::Trades, Book::BtcMxn)
+        .await
+        .unwrap();
+    assert_eq!(
+        socket.subscriptions(),
+        &[(Subscription::Trades, Book::BtcMxn)]
+    );
+    socket
+        .unsubscribe(Subscription::Trades, Book::BtcMxn)
+        .await
+        .unwrap();
+    assert!(socket.subscriptions().is_empty());
+    assert!(matches!(
+        socket.next().await,
+        Some(Err(BitsoError::WebSocket(_)))
+    ));
+}
+
+/// Test that a server that never answers the handshake hits the connect timeout
+#[tokio::test]
+async fn test_async_websocket_connect_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let config = WebSocketConfig::default()
+        .url(&format!("ws://{}", listener.local_addr().unwrap()))
+        .connect_timeout(Duration::from_millis(200))
+        .build();
+    let start = Instant::now();
+    assert!(AsyncBitsoWebSocket::with_config(config).await.is_err());
+    assert!(start.elapsed() < Duration::from_secs(5));
+    drop(listener);
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "blocking")]
+extern crate bitsors;
+
+mod common;
+
+use bitsors::blocking::{Bitso, OptionalOrderParams, OrderSide, OrderType};
+use bitsors::book::Book;
+use bitsors::transport::MemoryTransport;
+use common::blocking_bitso;
+use reqwest::{Method, StatusCode};
+use rust_decimal_macros::dec;
+
+/// Test a public request without an async runtime
+#[test]
+fn test_blocking_ticker() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/ticker/",
+        StatusCode::OK,
+        r#"{
+            "success": true,
+            "payload": {
+                "book": "btc_mxn",
+                "volume": "22.31349615",
+                "high": "5750.00",
+                "last": "5633.98",
+                "low": "5450.00",
+                "vwap": "5393.45",
+                "ask": "5632.24",
+                "bid": "5520.01",
+                "created_at": "2016-04-08T17:52:31.000+00:00"
+            }
+        }"#,
+    );
+    let result = blocking_bitso(&transport).get_ticker(&Book::BtcMxn);
+    println!("{:?}", result);
+    assert_eq!(result.unwrap().payload.last, Some(dec!(5633.98)));
+}
+
+/// Test private requests without an async runtime
+#[test]
+fn test_blocking_private_requests() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/balance/",
+        StatusCode::OK,
+        r#"{
+            "success": true,
+            "payload": {
+                "balances": [{
+                    "currency": "mxn",
+                    "total": "100.1234",
+                    "locked": "25.1234",
+                    "available": "75.0000"
+                }]
+            }
+        }"#,
+    );
+    transport.respond(
+        Method::POST,
+        "/v3/orders/",
+        StatusCode::OK,
+        r#"{"success": true, "payload": {"oid": "qlbga6b600n3xta7"}}"#,
+    );
+    let bitso = blocking_bitso(&transport);
+    let balances = bitso.get_account_balance().unwrap().payload.balances;
+    assert_eq!(balances[0].currency.as_deref(), Some("mxn"));
+    let optional_order_params = OptionalOrderParams {
+        major: Some(dec!(0.0001)),
+        minor: None,
+        price: None,
+        stop: None,
+        time_in_force: None,
+        origin_id: None,
+    };
+    let result = bitso.place_order(
+        &Book::BtcMxn,
+        OrderSide::Sell,
+        OrderType::Market,
+        Some(optional_order_params),
+    );
+    assert!(result.is_ok());
+    // Clones can be used from other threads
+    let other = bitso.clone();
+    let handle = std::thread::spawn(move || other.get_account_balance().is_ok());
+    assert!(handle.join().unwrap());
+    assert_eq!(transport.requests().len(), 3);
+}
+
+/// Test that errors are the same as in the async client
+#[test]
+fn test_blocking_missing_credentials() {
+    let bitso = Bitso::default().build();
+    let result = bitso.get_account_status();
+    assert!(matches!(
+        result,
+        Err(bitsors::error::BitsoError::MissingCredentials)
+    ));
+}
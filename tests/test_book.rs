@@ -0,0 +1,68 @@
+extern crate bitsors;
+
+use bitsors::book::Book;
+use bitsors::model::public::AvailableBook;
+use bitsors::model::websocket::Trades;
+use std::collections::HashSet;
+
+/// Test that known and unknown books convert from and to their names
+#[test]
+fn test_book_names() {
+    assert_eq!(Book::from("btc_mxn"), Book::BtcMxn);
+    assert_eq!(Book::from("BTC_MXN"), Book::BtcMxn);
+    assert_eq!(Book::new("ETH", "mxn"), Book::EthMxn);
+    assert!(Book::BtcMxn.is_known());
+
+    let book: Book = "sol_mxn".parse().unwrap();
+    assert!(matches!(book, Book::Other(ref name) if name == "sol_mxn"));
+    assert!(!book.is_known());
+    assert_eq!(book.to_string(), "sol_mxn");
+    assert_eq!(book.base(), "sol");
+    assert_eq!(book.quote(), "mxn");
+    assert_eq!(book, "sol_mxn");
+
+    // An unknown variant holding a known name is the same book
+    assert_eq!(Book::Other("btc_mxn".to_owned()), Book::BtcMxn);
+    let books: HashSet<Book> = vec![Book::Other("btc_mxn".to_owned()), Book::BtcMxn]
+        .into_iter()
+        .collect();
+    assert_eq!(books.len(), 1);
+
+    assert_eq!(Book::iter().count(), Book::KNOWN.len());
+    assert!(Book::iter().all(|book| book.is_known()));
+}
+
+/// Test that books serialize as their names and unknown books deserialize
+#[test]
+fn test_book_serde() {
+    assert_eq!(
+        serde_json::to_string(&Book::BtcMxn).unwrap(),
+        r#""btc_mxn""#
+    );
+    assert_eq!(
+        serde_json::to_string(&Book::new("sol", "mxn")).unwrap(),
+        r#""sol_mxn""#
+    );
+    assert_eq!(
+        serde_json::from_str::<Book>(r#""eth_mxn""#).unwrap(),
+        Book::EthMxn
+    );
+
+    let available: AvailableBook = serde_json::from_value(serde_json::json!({
+        "book": "sol_mxn",
+        "minimum_amount": "0.01",
+        "maximum_amount": "1000",
+        "minimum_price": "1",
+        "maximum_price": "100000",
+        "minimum_value": "10",
+        "maximum_value": "1000000"
+    }))
+    .unwrap();
+    assert_eq!(available.book, Some(Book::new("sol", "mxn")));
+
+    let trades: Trades = serde_json::from_str(
+        r#"{"type":"trades","book":"sol_mxn","payload":[{"i":1,"a":"0.1","r":"500","v":"50"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(trades.book, Book::Other("sol_mxn".to_owned()));
+}
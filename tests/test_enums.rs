@@ -0,0 +1,71 @@
+extern crate bitsors;
+
+use bitsors::model::private::{LedgerInstance, LookupOrdersPayload};
+use bitsors::model::{
+    FundingStatus, LedgerOperation, OrderSide, OrderStatus, OrderType, TimeInForce,
+    WithdrawalStatus,
+};
+
+/// Test that known values map to their variants and unknown values are kept
+#[test]
+fn test_enum_values() {
+    assert_eq!(OrderSide::from("buy"), OrderSide::Buy);
+    assert_eq!(OrderSide::from("SELL"), OrderSide::Sell);
+    assert_eq!(OrderType::from("limit"), OrderType::Limit);
+    assert_eq!(
+        OrderStatus::from("partial-fill"),
+        OrderStatus::PartiallyFilled
+    );
+    assert_eq!(
+        OrderStatus::from("Partially Filled"),
+        OrderStatus::PartiallyFilled
+    );
+    assert_eq!(TimeInForce::PostOnly.as_str(), "postonly");
+    assert_eq!(
+        WithdrawalStatus::from("complete"),
+        WithdrawalStatus::Complete
+    );
+    assert_eq!(FundingStatus::from("pending"), FundingStatus::Pending);
+    assert_eq!(LedgerOperation::Fee.to_string(), "fee");
+
+    let status: OrderStatus = "expired".parse().unwrap();
+    assert_eq!(status, OrderStatus::Other("expired".to_owned()));
+    assert!(!status.is_known());
+    assert_eq!(status.as_str(), "expired");
+    assert!(OrderStatus::Open.is_known());
+    // An unknown variant holding a known value is the same value
+    assert_eq!(OrderSide::Other("buy".to_owned()), OrderSide::Buy);
+}
+
+/// Test that typed fields deserialize, unknown values included,
+/// and serialize back to the values Bitso sends
+#[test]
+fn test_enum_serde() {
+    let order: LookupOrdersPayload = serde_json::from_value(serde_json::json!({
+        "book": "btc_mxn",
+        "oid": "543cr2v32a1h684430tvcqx1b",
+        "side": "buy",
+        "status": "partially filled",
+        "type": "limit"
+    }))
+    .unwrap();
+    assert_eq!(order.side, Some(OrderSide::Buy));
+    assert_eq!(order.status, Some(OrderStatus::PartiallyFilled));
+    assert_eq!(order.r#type, Some(OrderType::Limit));
+
+    let json = serde_json::to_value(&order).unwrap();
+    assert_eq!(json["side"], "buy");
+    assert_eq!(json["status"], "partially filled");
+
+    let entry: LedgerInstance = serde_json::from_value(serde_json::json!({
+        "eid": "c4ca4238a0b923820dcc509a6f75849b",
+        "operation": "staking_reward",
+        "balance_updates": [],
+        "details": {}
+    }))
+    .unwrap();
+    assert_eq!(
+        entry.operation,
+        Some(LedgerOperation::Other("staking_reward".to_owned()))
+    );
+}
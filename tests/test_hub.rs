@@ -0,0 +1,201 @@
+extern crate bitsors;
+
+use bitsors::error::BitsoError;
+use bitsors::hub::{Backpressure, WebSocketHub};
+use bitsors::websocket::*;
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+use tungstenite::{accept, Message};
+
+fn trade(book: &str, id: i64) -> String {
+    format!(
+        r#"{{"type":"trades","book":"{}","payload":[{{"i":{},"a":"0.1","r":"500000","v":"50000"}}]}}"#,
+        book, id
+    )
+}
+
+fn trade_id(response: Option<Response>) -> u64 {
+    match response {
+        Some(Response::Trades(r)) => r.payload[0].i.0,
+        r => panic!("Did not get a Response::Trades: {:?}", r),
+    }
+}
+
+/// Test that subscriptions are shared, messages are routed by book
+/// and channel, and each backpressure policy is applied
+#[test]
+fn test_hub_fan_out() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = accept(stream).unwrap();
+        let mut requests = Vec::new();
+        for _ in 0..3 {
+            let request = socket.read_message().unwrap().into_text().unwrap();
+            let type_field = if request.contains("diff-orders") {
+                "diff-orders"
+            } else {
+                "trades"
+            };
+            socket
+                .write_message(Message::Text(format!(
+                    r#"{{"action":"subscribe","response":"ok","time":1,"type":"{}"}}"#,
+                    type_field
+                )))
+                .unwrap();
+            requests.push(request);
+        }
+        for frame in &[
+            trade("btc_mxn", 1),
+            trade("eth_mxn", 2),
+            r#"{"type":"diff-orders","book":"btc_mxn","sequence":1,"payload":[]}"#.to_owned(),
+            trade("btc_mxn", 3),
+            trade("btc_mxn", 4),
+        ] {
+            socket.write_message(Message::Text(frame.clone())).unwrap();
+        }
+        socket.close(None).unwrap();
+        socket.write_pending().ok();
+        requests
+    });
+
+    let mut hub = WebSocketHub::new(BitsoWebSocket::connect(&url).unwrap());
+    let all = hub
+        .subscribe(Subscription::Trades, Book::BtcMxn, 16, Backpressure::Block)
+        .unwrap();
+    let latest = hub
+        .subscribe(
+            Subscription::Trades,
+            Book::BtcMxn,
+            1,
+            Backpressure::DropOldest,
+        )
+        .unwrap();
+    let slow = hub
+        .subscribe(
+            Subscription::Trades,
+            Book::BtcMxn,
+            1,
+            Backpressure::Disconnect,
+        )
+        .unwrap();
+    let diffs = hub
+        .subscribe(
+            Subscription::DiffOrders,
+            Book::BtcMxn,
+            4,
+            Backpressure::Block,
+        )
+        .unwrap();
+    let eth = hub
+        .subscribe(Subscription::Trades, Book::EthMxn, 4, Backpressure::Block)
+        .unwrap();
+    let result = hub.run().join().unwrap();
+    // The server closed the connection
+    assert!(result.is_err());
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 3);
+    assert!(requests[0].contains(r#""book":"btc_mxn","type":"trades""#));
+    assert!(requests[1].contains(r#""book":"btc_mxn","type":"diff-orders""#));
+    assert!(requests[2].contains(r#""book":"eth_mxn","type":"trades""#));
+
+    assert_eq!(trade_id(all.recv()), 1);
+    assert_eq!(trade_id(all.recv()), 3);
+    assert_eq!(trade_id(all.recv()), 4);
+    assert!(all.recv().is_none());
+
+    assert_eq!(trade_id(latest.recv()), 4);
+    assert!(latest.recv().is_none());
+    assert_eq!(latest.dropped(), 2);
+    assert!(!latest.lagged());
+
+    assert_eq!(trade_id(slow.recv()), 1);
+    assert!(slow.recv().is_none());
+    assert!(slow.lagged());
+
+    match diffs.recv() {
+        Some(Response::DiffOrders(r)) => assert_eq!(r.sequence, 1),
+        r => panic!("Did not get a Response::DiffOrders: {:?}", r),
+    }
+    assert!(diffs.try_recv().is_none());
+
+    assert_eq!(trade_id(eth.recv()), 2);
+    assert!(eth.recv().is_none());
+}
+
+/// Test that a blocked consumer receives every message once it catches up
+#[test]
+fn test_hub_block() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = accept(stream).unwrap();
+        socket.read_message().unwrap();
+        socket
+            .write_message(Message::Text(
+                r#"{"action":"subscribe","response":"ok","time":1,"type":"trades"}"#.to_owned(),
+            ))
+            .unwrap();
+        for id in 1..=5 {
+            socket
+                .write_message(Message::Text(trade("btc_mxn", id)))
+                .unwrap();
+        }
+        socket.close(None).unwrap();
+        socket.write_pending().ok();
+    });
+
+    let mut hub = WebSocketHub::new(BitsoWebSocket::connect(&url).unwrap());
+    let trades = hub
+        .subscribe(Subscription::Trades, Book::BtcMxn, 1, Backpressure::Block)
+        .unwrap();
+    let handle = hub.run();
+    for id in 1..=5 {
+        assert_eq!(trade_id(trades.recv()), id);
+    }
+    assert!(trades.recv().is_none());
+    assert_eq!(trades.dropped(), 0);
+    assert!(handle.join().unwrap().is_err());
+    server.join().unwrap();
+}
+
+/// Test that a quiet connection does not stop the hub
+#[test]
+fn test_hub_stale() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = accept(stream).unwrap();
+        socket.read_message().unwrap();
+        socket
+            .write_message(Message::Text(
+                r#"{"action":"subscribe","response":"ok","time":1,"type":"trades"}"#.to_owned(),
+            ))
+            .unwrap();
+        thread::sleep(Duration::from_millis(300));
+        socket
+            .write_message(Message::Text(trade("btc_mxn", 1)))
+            .unwrap();
+        socket.close(None).unwrap();
+        socket.write_pending().ok();
+    });
+
+    let socket = BitsoWebSocket::connect(&url)
+        .unwrap()
+        .stale_timeout(Duration::from_millis(50));
+    let mut hub = WebSocketHub::new(socket);
+    let trades = hub
+        .subscribe(Subscription::Trades, Book::BtcMxn, 4, Backpressure::Block)
+        .unwrap();
+    let hub = hub.run();
+    assert_eq!(trade_id(trades.recv()), 1);
+    assert!(trades.recv().is_none());
+    // Only the closed connection stopped the hub
+    assert!(matches!(hub.join().unwrap(), Err(BitsoError::WebSocket(_))));
+    server.join().unwrap();
+}
@@ -0,0 +1,43 @@
+extern crate bitsors;
+
+use bitsors::model::private::BalanceDetails;
+use bitsors::model::{FundingId, OrderId, OriginId, TradeId, WithdrawalId};
+use std::collections::HashSet;
+
+/// Test that IDs serialize as the bare values Bitso sends
+#[test]
+fn test_ids_are_transparent() {
+    let details: BalanceDetails = serde_json::from_value(serde_json::json!({
+        "tid": 51756,
+        "oid": "wri0yg8miihs80ngk",
+        "fid": "fc23c28a23e8cfb8",
+        "wid": "c5b8d7f0768ee91d3b33bee648318688"
+    }))
+    .unwrap();
+    assert_eq!(details.tid, Some(TradeId(51756)));
+    assert_eq!(details.oid, Some(OrderId::from("wri0yg8miihs80ngk")));
+    assert_eq!(details.fid, Some(FundingId::from("fc23c28a23e8cfb8")));
+    assert_eq!(
+        details.wid,
+        Some(WithdrawalId::from("c5b8d7f0768ee91d3b33bee648318688"))
+    );
+
+    assert_eq!(
+        serde_json::to_value(&details).unwrap()["oid"],
+        "wri0yg8miihs80ngk"
+    );
+    assert_eq!(serde_json::to_value(TradeId(7)).unwrap(), 7);
+    assert_eq!(OriginId::from("my-order").to_string(), "my-order");
+    assert_eq!(OrderId::from("abc").as_str(), "abc");
+}
+
+/// Test that IDs can be used as keys
+#[test]
+fn test_ids_are_hashable() {
+    let oids: HashSet<OrderId> = vec![OrderId::from("a"), OrderId::from("b"), OrderId::from("a")]
+        .into_iter()
+        .collect();
+    assert_eq!(oids.len(), 2);
+    let tids: HashSet<TradeId> = vec![TradeId(1), TradeId(1)].into_iter().collect();
+    assert_eq!(tids.len(), 1);
+}
@@ -0,0 +1,181 @@
+extern crate bitsors;
+
+mod common;
+
+use bitsors::book::Book;
+use bitsors::error::{BitsoError, OrderError};
+use bitsors::model::public::AvailableBook;
+use bitsors::model::{OrderId, OrderSide, OrderType, OriginId, TimeInForce};
+use bitsors::order::OrderRequest;
+use bitsors::transport::MemoryTransport;
+use common::bitso;
+use reqwest::{Method, StatusCode};
+use rust_decimal_macros::dec;
+
+fn btc_mxn() -> AvailableBook {
+    serde_json::from_str(
+        r#"{"book":"btc_mxn","minimum_amount":".003","maximum_amount":"1000.00","minimum_price":"100.00","maximum_price":"1000000.00","minimum_value":"25.00","maximum_value":"1000000.00"}"#,
+    )
+    .unwrap()
+}
+
+/// Test that malformed orders are rejected before checking limits
+#[test]
+fn test_check_order() {
+    let limit = OrderRequest::limit(Book::BtcMxn, OrderSide::Buy, dec!(500000));
+    assert_eq!(limit.check(), Err(OrderError::MissingSize));
+    assert_eq!(
+        limit.clone().major(dec!(0.01)).minor(dec!(100)).check(),
+        Err(OrderError::MajorAndMinor)
+    );
+    assert_eq!(
+        limit.major(dec!(0)).check(),
+        Err(OrderError::NotPositive {
+            field: "major",
+            value: dec!(0)
+        })
+    );
+    assert_eq!(
+        OrderRequest::stop_limit(Book::BtcMxn, OrderSide::Sell, dec!(500000), dec!(-1))
+            .major(dec!(0.01))
+            .check(),
+        Err(OrderError::NotPositive {
+            field: "stop",
+            value: dec!(-1)
+        })
+    );
+    assert_eq!(
+        OrderRequest::market(Book::BtcMxn, OrderSide::Sell)
+            .major(dec!(0.01))
+            .time_in_force(TimeInForce::FillOrKill)
+            .check(),
+        Err(OrderError::TimeInForceOnMarketOrder)
+    );
+    assert_eq!(
+        OrderRequest::limit(Book::BtcMxn, OrderSide::from("short"), dec!(500000))
+            .major(dec!(0.01))
+            .check(),
+        Err(OrderError::Unknown {
+            field: "side",
+            value: "short".to_owned()
+        })
+    );
+    assert_eq!(
+        OrderRequest::limit(Book::BtcMxn, OrderSide::Buy, dec!(500000))
+            .major(dec!(0.01))
+            .time_in_force(TimeInForce::from("forever"))
+            .check(),
+        Err(OrderError::Unknown {
+            field: "time in force",
+            value: "forever".to_owned()
+        })
+    );
+}
+
+/// Test that orders are validated against the limits of their book
+#[test]
+fn test_validate_limits() {
+    let limits = btc_mxn();
+    let limit = OrderRequest::limit(Book::BtcMxn, OrderSide::Buy, dec!(500000));
+    assert!(limit.clone().major(dec!(0.01)).validate(&limits).is_ok());
+    assert!(limit.clone().minor(dec!(5000)).validate(&limits).is_ok());
+
+    // 0.001 BTC is below the minimum amount
+    match limit.clone().major(dec!(0.001)).validate(&limits) {
+        Err(OrderError::OutOfRange {
+            field,
+            value,
+            minimum,
+            ..
+        }) => {
+            assert_eq!(field, "amount");
+            assert_eq!(value, dec!(0.001));
+            assert_eq!(minimum, Some(dec!(0.003)));
+        }
+        e => panic!("Expected OrderError::OutOfRange, got {:?}", e),
+    }
+    // 1000 MXN at 500000 is also 0.002 BTC
+    match limit.minor(dec!(1000)).validate(&limits) {
+        Err(OrderError::OutOfRange { field, .. }) => assert_eq!(field, "amount"),
+        e => panic!("Expected OrderError::OutOfRange, got {:?}", e),
+    }
+    match OrderRequest::limit(Book::BtcMxn, OrderSide::Buy, dec!(2000000))
+        .major(dec!(0.01))
+        .validate(&limits)
+    {
+        Err(OrderError::OutOfRange { field, .. }) => assert_eq!(field, "price"),
+        e => panic!("Expected OrderError::OutOfRange, got {:?}", e),
+    }
+    match OrderRequest::market(Book::BtcMxn, OrderSide::Buy)
+        .minor(dec!(10))
+        .validate(&limits)
+    {
+        Err(OrderError::OutOfRange { field, .. }) => assert_eq!(field, "value"),
+        e => panic!("Expected OrderError::OutOfRange, got {:?}", e),
+    }
+    assert_eq!(
+        OrderRequest::market(Book::EthMxn, OrderSide::Buy)
+            .major(dec!(1))
+            .validate(&limits),
+        Err(OrderError::WrongBook {
+            order: Book::EthMxn,
+            limits: Book::BtcMxn
+        })
+    );
+}
+
+/// Test that valid orders are sent and invalid ones never reach the transport
+#[tokio::test]
+async fn test_place_order_request() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::POST,
+        "/v3/orders/",
+        StatusCode::OK,
+        r#"{"success": true, "payload": {"oid": "qlbga6b600n3xta7"}}"#,
+    );
+    let bitso = bitso(&transport);
+
+    let invalid = OrderRequest::market(Book::BtcMxn, OrderSide::Sell);
+    match bitso.place(&invalid).await.unwrap_err() {
+        BitsoError::InvalidOrder(OrderError::MissingSize) => {}
+        e => panic!("Expected BitsoError::InvalidOrder, got {:?}", e),
+    }
+    let unknown = OrderRequest::market(Book::BtcMxn, OrderSide::from("short")).major(dec!(0.01));
+    match bitso.place(&unknown).await.unwrap_err() {
+        BitsoError::InvalidOrder(OrderError::Unknown { field, .. }) => assert_eq!(field, "side"),
+        e => panic!("Expected BitsoError::InvalidOrder, got {:?}", e),
+    }
+    match bitso
+        .place_order(
+            &Book::BtcMxn,
+            OrderSide::Sell,
+            OrderType::from("iceberg"),
+            None,
+        )
+        .await
+        .unwrap_err()
+    {
+        BitsoError::InvalidOrder(OrderError::Unknown { field, .. }) => assert_eq!(field, "type"),
+        e => panic!("Expected BitsoError::InvalidOrder, got {:?}", e),
+    }
+    assert!(transport.requests().is_empty());
+
+    let order = OrderRequest::stop_limit(Book::BtcMxn, OrderSide::Sell, dec!(400000), dec!(410000))
+        .major(dec!(0.01))
+        .time_in_force(TimeInForce::GoodTillCancelled)
+        .origin_id(OriginId::from("my-order"));
+    let result = bitso.place(&order).await.unwrap();
+    assert_eq!(result.payload.oid, Some(OrderId::from("qlbga6b600n3xta7")));
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    let body = requests[0].body.as_ref().unwrap();
+    assert_eq!(body["book"], "btc_mxn");
+    assert_eq!(body["side"], "sell");
+    assert_eq!(body["type"], "limit");
+    assert_eq!(body["major"], "0.01");
+    assert_eq!(body["price"], "400000");
+    assert_eq!(body["stop"], "410000");
+    assert_eq!(body["origin_id"], "my-order");
+    assert!(body.get("minor").is_none());
+}
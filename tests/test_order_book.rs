@@ -0,0 +1,335 @@
+extern crate bitsors;
+
+use bitsors::book::Book;
+use bitsors::client::Bitso;
+use bitsors::error::BitsoError;
+use bitsors::model::websocket::DiffOrders;
+use bitsors::model::Decimal;
+use bitsors::order_book::{BookUpdate, LocalOrderBook, PriceLevel};
+use bitsors::transport::MemoryTransport;
+use reqwest::{Method, StatusCode};
+use rust_decimal_macros::dec;
+use std::time::Duration;
+
+fn snapshot(sequence: i64, bids: &[(&str, &str, &str)], asks: &[(&str, &str, &str)]) -> String {
+    let orders = |orders: &[(&str, &str, &str)]| {
+        orders
+            .iter()
+            .map(|(oid, price, amount)| {
+                format!(
+                    r#"{{"book":"btc_mxn","price":"{}","amount":"{}","oid":"{}"}}"#,
+                    price, amount, oid
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    format!(
+        r#"{{"success":true,"payload":{{"asks":[{}],"bids":[{}],"updated_at":"2016-04-08T17:52:31.000+00:00","sequence":"{}"}}}}"#,
+        orders(asks),
+        orders(bids),
+        sequence
+    )
+}
+
+fn diff(sequence: i64, orders: &str) -> DiffOrders {
+    serde_json::from_str(&format!(
+        r#"{{"type":"diff-orders","book":"btc_mxn","sequence":{},"payload":[{}]}}"#,
+        sequence, orders
+    ))
+    .unwrap()
+}
+
+async fn order_book(transport: &MemoryTransport) -> LocalOrderBook {
+    let bitso = Bitso::default().transport(transport.clone()).build();
+    LocalOrderBook::new(bitso, &Book::BtcMxn).await.unwrap()
+}
+
+fn level(price: Decimal, amount: Decimal, orders: usize) -> Option<PriceLevel> {
+    Some(PriceLevel {
+        price,
+        amount,
+        orders,
+    })
+}
+
+/// Test that the snapshot is aggregated into price levels
+#[tokio::test]
+async fn test_snapshot() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/order_book/",
+        StatusCode::OK,
+        &snapshot(
+            10,
+            &[
+                ("b1", "99", "1"),
+                ("b2", "100", "0.5"),
+                ("b3", "100", "0.25"),
+            ],
+            &[("a1", "102", "2"), ("a2", "101", "1")],
+        ),
+    );
+    let book = order_book(&transport).await;
+    assert_eq!(book.sequence(), 10);
+    assert!(book.is_synced());
+    assert_eq!(book.best_bid(), level(dec!(100), dec!(0.75), 2));
+    assert_eq!(book.best_ask(), level(dec!(101), dec!(1), 1));
+    assert_eq!(book.bids(5).len(), 2);
+    assert_eq!(book.asks(1), vec![level(dec!(101), dec!(1), 1).unwrap()]);
+    let request = &transport.requests()[0];
+    assert_eq!(request.query_param("aggregate").as_deref(), Some("false"));
+}
+
+/// Test that diffs add, update and remove orders, and stale diffs are dropped
+#[tokio::test]
+async fn test_apply_diffs() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/order_book/",
+        StatusCode::OK,
+        &snapshot(10, &[("b1", "100", "1")], &[("a1", "101", "1")]),
+    );
+    let mut book = order_book(&transport).await;
+
+    let update = book
+        .apply(&diff(
+            11,
+            r#"{"d":1,"r":"100.5","t":0,"o":"b2","s":"open","a":"2","v":"201"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(update, BookUpdate::Applied);
+    assert_eq!(book.best_bid(), level(dec!(100.5), dec!(2), 1));
+
+    book.apply(&diff(
+        12,
+        r#"{"d":2,"r":"100.5","t":0,"o":"b2","s":"cancelled"}"#,
+    ))
+    .await
+    .unwrap();
+    assert_eq!(book.best_bid(), level(dec!(100), dec!(1), 1));
+
+    book.apply(&diff(
+        13,
+        r#"{"d":3,"r":"101","t":1,"o":"a1","s":"open","a":"0.4","v":"40.4"}"#,
+    ))
+    .await
+    .unwrap();
+    assert_eq!(book.best_ask(), level(dec!(101), dec!(0.4), 1));
+
+    book.apply(&diff(
+        14,
+        r#"{"d":4,"r":"101","t":1,"o":"a1","s":"completed"}"#,
+    ))
+    .await
+    .unwrap();
+    assert_eq!(book.best_ask(), None);
+    assert_eq!(book.sequence(), 14);
+
+    let update = book
+        .apply(&diff(
+            9,
+            r#"{"d":0,"r":"50","t":0,"o":"old","s":"open","a":"1","v":"50"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(update, BookUpdate::Stale);
+    assert_eq!(book.best_bid(), level(dec!(100), dec!(1), 1));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+/// Test that a sequence gap fetches a new snapshot and replays newer diffs
+#[tokio::test]
+async fn test_sequence_gap() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/order_book/",
+        StatusCode::OK,
+        &snapshot(10, &[("b1", "100", "1")], &[]),
+    );
+    transport.respond(
+        Method::GET,
+        "/v3/order_book/",
+        StatusCode::OK,
+        &snapshot(12, &[("b1", "100", "1"), ("b3", "98", "3")], &[]),
+    );
+    let mut book = order_book(&transport).await;
+
+    let update = book
+        .apply(&diff(
+            13,
+            r#"{"d":1,"r":"105","t":1,"o":"a1","s":"open","a":"1","v":"105"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(update, BookUpdate::Resynced);
+    assert_eq!(transport.requests().len(), 2);
+    assert!(book.is_synced());
+    assert_eq!(book.sequence(), 13);
+    assert_eq!(book.bids(5)[1], level(dec!(98), dec!(3), 1).unwrap());
+    assert_eq!(book.best_ask(), level(dec!(105), dec!(1), 1));
+}
+
+/// Test that diffs are buffered while the snapshot is behind the stream
+#[tokio::test]
+async fn test_snapshot_behind_stream() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/order_book/",
+        StatusCode::OK,
+        &snapshot(10, &[("b1", "100", "1")], &[]),
+    );
+    transport.respond(
+        Method::GET,
+        "/v3/order_book/",
+        StatusCode::OK,
+        &snapshot(10, &[("b1", "100", "1")], &[]),
+    );
+    transport.respond(
+        Method::GET,
+        "/v3/order_book/",
+        StatusCode::OK,
+        &snapshot(11, &[("b1", "100", "1"), ("b2", "99", "1")], &[]),
+    );
+    let mut book = order_book(&transport)
+        .await
+        .resync_delay(Duration::from_millis(10));
+
+    let update = book
+        .apply(&diff(
+            12,
+            r#"{"d":1,"r":"101","t":0,"o":"b3","s":"open","a":"1","v":"101"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(update, BookUpdate::OutOfSync);
+    assert!(!book.is_synced());
+    assert_eq!(book.best_bid(), level(dec!(100), dec!(1), 1));
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let update = book
+        .apply(&diff(
+            13,
+            r#"{"d":2,"r":"100","t":0,"o":"b1","s":"cancelled"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(update, BookUpdate::Resynced);
+    assert!(book.is_synced());
+    assert_eq!(book.sequence(), 13);
+    assert_eq!(book.best_bid(), level(dec!(101), dec!(1), 1));
+    assert_eq!(book.bids(5).len(), 2);
+}
+
+/// Test that diffs arriving while out of sync are buffered without
+/// fetching a snapshot for each of them
+#[tokio::test]
+async fn test_resync_backoff() {
+    let transport = MemoryTransport::new();
+    for _ in 0..3 {
+        transport.respond(
+            Method::GET,
+            "/v3/order_book/",
+            StatusCode::OK,
+            &snapshot(10, &[("b1", "100", "1")], &[]),
+        );
+    }
+    transport.respond(
+        Method::GET,
+        "/v3/order_book/",
+        StatusCode::OK,
+        &snapshot(11, &[("b1", "100", "1")], &[]),
+    );
+    let mut book = order_book(&transport)
+        .await
+        .resync_delay(Duration::from_millis(50));
+
+    for sequence in 12..32 {
+        let update = book
+            .apply(&diff(
+                sequence,
+                r#"{"d":1,"r":"101","t":0,"o":"b2","s":"open","a":"1","v":"101"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(update, BookUpdate::OutOfSync);
+    }
+    // The initial snapshot plus a single refetch for the gap
+    assert_eq!(transport.requests().len(), 2);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    book.apply(&diff(32, "")).await.unwrap();
+    assert_eq!(transport.requests().len(), 3);
+    // The delay doubled after the second miss
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    book.apply(&diff(33, "")).await.unwrap();
+    assert_eq!(transport.requests().len(), 3);
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let update = book.apply(&diff(34, "")).await.unwrap();
+    assert_eq!(update, BookUpdate::Resynced);
+    assert_eq!(book.sequence(), 34);
+    assert_eq!(transport.requests().len(), 4);
+}
+
+/// Test that a failed snapshot fetch leaves the book out of sync and that
+/// the diffs buffered meanwhile are capped
+#[tokio::test]
+async fn test_resync_failure() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/order_book/",
+        StatusCode::OK,
+        &snapshot(10, &[("b1", "100", "1")], &[]),
+    );
+    transport.respond(
+        Method::GET,
+        "/v3/order_book/",
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "oops",
+    );
+    let mut book = order_book(&transport)
+        .await
+        .resync_delay(Duration::from_secs(60))
+        .max_pending(3);
+
+    assert!(book.apply(&diff(12, "")).await.is_err());
+    assert!(!book.is_synced());
+    assert_eq!(book.sequence(), 10);
+    assert_eq!(book.best_bid(), level(dec!(100), dec!(1), 1));
+
+    for sequence in 13..20 {
+        let update = book.apply(&diff(sequence, "")).await.unwrap();
+        assert_eq!(update, BookUpdate::OutOfSync);
+        assert!(book.pending() <= 3);
+    }
+    assert!(!book.is_synced());
+    assert_eq!(transport.requests().len(), 2);
+}
+
+/// Test that aggregated snapshots and diffs for other books are rejected
+#[tokio::test]
+async fn test_invalid_input() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/order_book/",
+        StatusCode::OK,
+        r#"{"success":true,"payload":{"asks":[],"bids":[{"book":"btc_mxn","price":"100","amount":"1"}],"updated_at":null,"sequence":"1"}}"#,
+    );
+    let bitso = Bitso::default().transport(transport.clone()).build();
+    let result = LocalOrderBook::new(bitso.clone(), &Book::BtcMxn).await;
+    assert!(matches!(result, Err(BitsoError::Validation(_))));
+
+    let snapshot: bitsors::model::public::OrderBookPayload =
+        serde_json::from_str(r#"{"asks":[],"bids":[],"updated_at":null,"sequence":"1"}"#).unwrap();
+    let mut book = LocalOrderBook::from_snapshot(bitso, &Book::EthMxn, snapshot).unwrap();
+    let result = book.apply(&diff(2, "")).await;
+    assert!(matches!(result, Err(BitsoError::Validation(_))));
+}
@@ -0,0 +1,183 @@
+extern crate bitsors;
+
+mod common;
+
+use bitsors::book::Book;
+use bitsors::client::OptionalOrderParams;
+use bitsors::error::{BitsoError, OrderError};
+use bitsors::model::public::AvailableBook;
+use bitsors::model::{Decimal, OrderSide, OrderType};
+use bitsors::order::OrderRequest;
+use bitsors::precision::{Precision, Rounding};
+use bitsors::registry::BookRegistry;
+use bitsors::transport::MemoryTransport;
+use common::bitso;
+use reqwest::{Method, StatusCode};
+use rust_decimal_macros::dec;
+
+/// Test rounding down, up and to the nearest tick
+#[test]
+fn test_rounding() {
+    let precision = Precision::for_book(&Book::BtcMxn);
+    assert_eq!(precision.price_tick, dec!(0.01));
+    assert_eq!(precision.amount_tick, dec!(0.00000001));
+    assert_eq!(precision.value_tick, dec!(0.01));
+
+    let precision = precision.price_tick(dec!(5));
+    assert_eq!(
+        precision.round_price(dec!(101.2), Rounding::Down),
+        dec!(100)
+    );
+    assert_eq!(precision.round_price(dec!(101.2), Rounding::Up), dec!(105));
+    assert_eq!(
+        precision.round_price(dec!(102.5), Rounding::Nearest),
+        dec!(105)
+    );
+    assert_eq!(
+        precision.round_price(dec!(102.4), Rounding::Nearest),
+        dec!(100)
+    );
+    assert_eq!(precision.round_price(dec!(100), Rounding::Up), dec!(100));
+    assert_eq!(
+        precision.round_amount(dec!(0.123456789), Rounding::Down),
+        dec!(0.12345678)
+    );
+    assert_eq!(
+        precision.round_value(dec!(10.005), Rounding::Up),
+        dec!(10.01)
+    );
+    assert!(precision.is_valid_price(dec!(105.00)));
+    assert!(!precision.is_valid_price(dec!(101)));
+    assert!(!precision.is_valid_amount(dec!(0.000000001)));
+
+    let eth_btc = Precision::for_book(&Book::EthBtc);
+    assert_eq!(eth_btc.price_tick, dec!(0.00000001));
+
+    // Too large to divide by the tick, so left as is
+    let huge = Decimal::MAX;
+    assert_eq!(eth_btc.round_amount(huge, Rounding::Up), huge);
+}
+
+/// Test that only the tick size listed for a book is enforced
+#[test]
+fn test_from_available_book() {
+    let listed = r#"{"book":"btc_mxn","minimum_amount":".003","maximum_amount":"1000.00","minimum_price":"100.00","maximum_price":"1000000.00","minimum_value":"25.00","maximum_value":"1000000.00""#;
+    let available: AvailableBook =
+        serde_json::from_str(&format!(r#"{},"tick_size":"10"}}"#, listed)).unwrap();
+    let precision = Precision::from_available_book(&available);
+    assert_eq!(precision.price_tick, dec!(10));
+    assert_eq!(precision.amount_tick, dec!(0));
+    assert_eq!(precision.value_tick, dec!(0));
+
+    let order =
+        OrderRequest::limit(Book::BtcMxn, OrderSide::Buy, dec!(500005)).major(dec!(0.0123456789));
+    assert_eq!(
+        order.validate(&available),
+        Err(OrderError::TooPrecise {
+            field: "price",
+            value: dec!(500005),
+            tick: dec!(10)
+        })
+    );
+    let rounded = order.clone().round(&precision, Rounding::Down);
+    assert_eq!(rounded.price(), Some(dec!(500000)));
+    assert_eq!(rounded.major_size(), Some(dec!(0.0123456789)));
+    assert!(rounded.validate(&available).is_ok());
+
+    // Without a listed tick size nothing is guessed
+    let available: AvailableBook = serde_json::from_str(&format!("{}}}", listed)).unwrap();
+    assert!(order.validate(&available).is_ok());
+}
+
+/// Test that both ways of placing orders enforce the configured precision
+#[tokio::test]
+async fn test_place_enforces_precision() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::POST,
+        "/v3/orders/",
+        StatusCode::OK,
+        r#"{"success": true, "payload": {"oid": "qlbga6b600n3xta7"}}"#,
+    );
+    let bitso = bitso(&transport)
+        .precision(
+            Book::BtcMxn,
+            Precision::for_book(&Book::BtcMxn).price_tick(dec!(10)),
+        )
+        .build();
+
+    let order = OrderRequest::limit(Book::BtcMxn, OrderSide::Buy, dec!(500005)).major(dec!(0.01));
+    match bitso.place(&order).await.unwrap_err() {
+        BitsoError::InvalidOrder(OrderError::TooPrecise { field, tick, .. }) => {
+            assert_eq!(field, "price");
+            assert_eq!(tick, dec!(10));
+        }
+        e => panic!("Expected BitsoError::InvalidOrder, got {:?}", e),
+    }
+    let params = OptionalOrderParams {
+        major: None,
+        minor: Some(dec!(100.001)),
+        price: None,
+        stop: None,
+        time_in_force: None,
+        origin_id: None,
+    };
+    match bitso
+        .place_order(
+            &Book::BtcMxn,
+            OrderSide::Buy,
+            OrderType::Market,
+            Some(params),
+        )
+        .await
+        .unwrap_err()
+    {
+        BitsoError::InvalidOrder(OrderError::TooPrecise { field, .. }) => {
+            assert_eq!(field, "minor")
+        }
+        e => panic!("Expected BitsoError::InvalidOrder, got {:?}", e),
+    }
+    assert!(transport.requests().is_empty());
+
+    let order = order.round(
+        &bitso.precision_for(&Book::BtcMxn).unwrap(),
+        Rounding::Nearest,
+    );
+    assert!(bitso.place(&order).await.is_ok());
+    let body = transport.requests()[0].body.clone().unwrap();
+    assert_eq!(body["price"], "500010");
+
+    // Books without a configured precision are sent unchecked
+    assert!(bitso.precision_for(&Book::EthMxn).is_none());
+    let order =
+        OrderRequest::limit(Book::EthMxn, OrderSide::Buy, dec!(50000.005)).major(dec!(0.01));
+    assert!(bitso.place(&order).await.is_ok());
+    assert_eq!(transport.requests().len(), 2);
+}
+
+/// Test that the increments listed in a registry are enforced
+#[tokio::test]
+async fn test_precisions_from_registry() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/available_books/",
+        StatusCode::OK,
+        r#"{"success":true,"payload":[{"book":"btc_mxn","minimum_amount":".003","maximum_amount":"1000.00","minimum_price":"100.00","maximum_price":"1000000.00","minimum_value":"25.00","maximum_value":"1000000.00","tick_size":"10"}]}"#,
+    );
+    let registry = BookRegistry::load(bitso(&transport)).await.unwrap();
+    let bitso = bitso(&transport).precisions_from(&registry).build();
+    assert_eq!(
+        bitso.precision_for(&Book::BtcMxn).map(|p| p.price_tick),
+        Some(dec!(10))
+    );
+
+    let order = OrderRequest::limit(Book::BtcMxn, OrderSide::Buy, dec!(500005)).major(dec!(0.01));
+    match bitso.place(&order).await.unwrap_err() {
+        BitsoError::InvalidOrder(OrderError::TooPrecise { field, .. }) => {
+            assert_eq!(field, "price")
+        }
+        e => panic!("Expected BitsoError::InvalidOrder, got {:?}", e),
+    }
+    assert_eq!(transport.requests().len(), 1);
+}
@@ -4,8 +4,12 @@ extern crate mockito;
 extern crate lazy_static;
 
 use bitsors::auth::BitsoCredentials;
-use bitsors::client::{Bitso, OptionalOrderParams, OptionalParams};
+use bitsors::book::Book;
+use bitsors::client::{Bitso, CancelTarget, OptionalOrderParams, OptionalParams};
+use bitsors::error::BitsoError;
+use bitsors::model::{FundingId, OrderId, OrderSide, OrderType, OriginId, WithdrawalId};
 use mockito::{mock, Matcher};
+use rust_decimal_macros::dec;
 use std::sync::Mutex;
 
 lazy_static! {
@@ -15,7 +19,7 @@ lazy_static! {
     static ref CLIENT_CREDENTIAL: Mutex<BitsoCredentials> = Mutex::new(BitsoCredentials::default().build());
 }
 
-/// *** PRIVATE API *** ///
+// *** PRIVATE API *** //
 
 /// Test unsuccessful request due to empty credentials
 #[tokio::test]
@@ -24,6 +28,7 @@ async fn test_empty_credentials() {
     let result = bitso.get_account_status().await;
     assert!(result.is_err()); // Empty credentials
     println!("{:?}", result);
+    assert!(matches!(result, Err(BitsoError::MissingCredentials)));
 }
 
 /// Test successful request to get account status
@@ -498,9 +503,9 @@ async fn test_withdrawals_wids() {
     let result = bitso
         .get_withdrawals(
             None,
-            Some(vec![
-                "c5b8d7f0768ee91d3b33bee648318688",
-                "p4u8d7f0768ee91d3b33bee6483132i8",
+            Some(&[
+                WithdrawalId::from("c5b8d7f0768ee91d3b33bee648318688"),
+                WithdrawalId::from("p4u8d7f0768ee91d3b33bee6483132i8"),
             ]),
             None,
             None,
@@ -541,7 +546,7 @@ async fn test_withdrawals_wid() {
         .build();
     let result = bitso
         .get_withdrawals(
-            Some("c5b8d7f0768ee91d3b33bee648318688"),
+            Some(&WithdrawalId::from("c5b8d7f0768ee91d3b33bee648318688")),
             None,
             None,
             None,
@@ -581,7 +586,7 @@ async fn test_fundings_fid() {
         .build();
     let result = bitso
         .get_fundings(
-            Some("c5b8d7f0768ee91d3b33bee648318688"),
+            Some(&FundingId::from("c5b8d7f0768ee91d3b33bee648318688")),
             None,
             None,
             None,
@@ -682,7 +687,7 @@ async fn test_user_trades() {
         .prefix(mockito::server_url().as_str())
         .client_credentials_manager(CLIENT_CREDENTIAL.lock().unwrap().clone())
         .build();
-    let result = bitso.get_user_trades("btc_mxn", None, None, None).await;
+    let result = bitso.get_user_trades(&Book::BtcMxn, None, None, None).await;
     assert!(result.is_ok());
     println!("{:?}", result);
 }
@@ -731,7 +736,9 @@ async fn test_order_trades() {
         .prefix(mockito::server_url().as_str())
         .client_credentials_manager(CLIENT_CREDENTIAL.lock().unwrap().clone())
         .build();
-    let result = bitso.get_order_trades(Some("Jvqrschkgdkc1go3"), None).await;
+    let result = bitso
+        .get_order_trades(Some(&OrderId::from("Jvqrschkgdkc1go3")), None)
+        .await;
     assert!(result.is_ok());
     println!("{:?}", result);
 }
@@ -769,7 +776,9 @@ async fn test_order_trades_origin_id() {
         .prefix(mockito::server_url().as_str())
         .client_credentials_manager(CLIENT_CREDENTIAL.lock().unwrap().clone())
         .build();
-    let result = bitso.get_order_trades(None, Some("origin_id1")).await;
+    let result = bitso
+        .get_order_trades(None, Some(&OriginId::from("origin_id1")))
+        .await;
     assert!(result.is_ok());
     println!("{:?}", result);
 }
@@ -831,7 +840,7 @@ async fn test_open_orders() {
         .prefix(mockito::server_url().as_str())
         .client_credentials_manager(CLIENT_CREDENTIAL.lock().unwrap().clone())
         .build();
-    let result = bitso.get_open_orders(Some("btc_mxn"), None).await;
+    let result = bitso.get_open_orders(Some(&Book::BtcMxn), None).await;
     assert!(result.is_ok());
     println!("{:?}", result);
 }
@@ -901,7 +910,7 @@ async fn test_open_orders_optional_params() {
         limit: Some(&1),
     };
     let result = bitso
-        .get_open_orders(Some("btc_mxn"), Some(optional_params))
+        .get_open_orders(Some(&Book::BtcMxn), Some(optional_params))
         .await;
     println!("{:?}", result);
     assert!(result.is_ok());
@@ -954,7 +963,10 @@ async fn test_lookup_orders_with_optional_params() {
     let result = bitso
         .get_lookup_orders(
             None,
-            Some(vec!["543cr2v32a1h6844", "qlbga6b600n3xta7a"]),
+            Some(&[
+                OrderId::from("543cr2v32a1h6844"),
+                OrderId::from("qlbga6b600n3xta7a"),
+            ]),
             None,
         )
         .await;
@@ -991,7 +1003,7 @@ async fn test_lookup_orders() {
         .client_credentials_manager(CLIENT_CREDENTIAL.lock().unwrap().clone())
         .build();
     let result = bitso
-        .get_lookup_orders(Some("543cr2v32a1h6844"), None, None)
+        .get_lookup_orders(Some(&OrderId::from("543cr2v32a1h6844")), None, None)
         .await;
     assert!(result.is_ok());
     println!("{:?}", result);
@@ -1017,9 +1029,8 @@ async fn test_cancel_order() {
         .prefix(mockito::server_url().as_str())
         .client_credentials_manager(CLIENT_CREDENTIAL.lock().unwrap().clone())
         .build();
-    let result = bitso
-        .cancel_order(false, Some("cME2F7uZKJcMKXqU"), None, None)
-        .await;
+    let oid = OrderId::from("cME2F7uZKJcMKXqU");
+    let result = bitso.cancel_order(CancelTarget::Oid(&oid)).await;
     assert!(result.is_ok());
     println!("{:?}", result);
 }
@@ -1044,7 +1055,7 @@ async fn test_cancel_order_all() {
         .prefix(mockito::server_url().as_str())
         .client_credentials_manager(CLIENT_CREDENTIAL.lock().unwrap().clone())
         .build();
-    let result = bitso.cancel_order(true, None, None, None).await;
+    let result = bitso.cancel_order(CancelTarget::All).await;
     assert!(result.is_ok());
     println!("{:?}", result);
 }
@@ -1067,7 +1078,9 @@ async fn test_place_order() {
         .prefix(mockito::server_url().as_str())
         .client_credentials_manager(CLIENT_CREDENTIAL.lock().unwrap().clone())
         .build();
-    let result = bitso.place_order("btc_mxn", "sell", "market", None).await;
+    let result = bitso
+        .place_order(&Book::BtcMxn, OrderSide::Sell, OrderType::Market, None)
+        .await;
     assert!(result.is_ok());
     println!("{:?}", result);
 }
@@ -1091,7 +1104,7 @@ async fn test_place_order_with_optional_params() {
         .client_credentials_manager(CLIENT_CREDENTIAL.lock().unwrap().clone())
         .build();
     let optional_order_params = OptionalOrderParams {
-        major: Some("0.0001"),
+        major: Some(dec!(0.0001)),
         minor: None,
         price: None,
         stop: None,
@@ -1099,7 +1112,12 @@ async fn test_place_order_with_optional_params() {
         origin_id: None,
     };
     let result = bitso
-        .place_order("btc_mxn", "sell", "market", Some(optional_order_params))
+        .place_order(
+            &Book::BtcMxn,
+            OrderSide::Sell,
+            OrderType::Market,
+            Some(optional_order_params),
+        )
         .await;
     assert!(result.is_ok());
     println!("{:?}", result);
@@ -1,8 +1,12 @@
 extern crate bitsors;
 extern crate mockito;
 
-use bitsors::client::{Bitso, OptionalParams};
+use bitsors::book::Book;
+use bitsors::client::{ApiError, Bitso, OptionalParams};
+use bitsors::error::{BitsoError, ErrorCategory, ErrorCode};
+use bitsors::http::HttpConfig;
 use mockito::{mock, Matcher};
+use rust_decimal_macros::dec;
 
 /// Test unsuccesful request and error parsing
 #[tokio::test]
@@ -23,13 +27,103 @@ async fn test_error_parsing() {
     let bitso = Bitso::default()
         .prefix(mockito::server_url().as_str())
         .build();
-    let result = bitso.get_ticker("FAKEORDERBOOK").await;
+    let result = bitso
+        .get_ticker(&Book::Other("FAKEORDERBOOK".to_owned()))
+        .await;
     assert!(result.is_err());
     println!("{:?}", result);
+    let error = result.unwrap_err();
     assert_eq!(
-        result.unwrap_err().to_string(),
+        error.to_string(),
         "Bitso API error code 0301: Unknown OrderBook FAKEORDERBOOK"
     );
+    match error {
+        BitsoError::Api {
+            status,
+            error: ApiError::RegularError { code, .. },
+        } => {
+            assert_eq!(status.as_u16(), 400);
+            assert_eq!(code, ErrorCode::UnknownOrderBook);
+            assert_eq!(code.category(), ErrorCategory::InvalidOrderParams);
+            assert!(!code.is_retryable());
+        }
+        e => panic!("Expected BitsoError::Api, got {:?}", e),
+    }
+}
+
+/// Test that unknown error codes are kept instead of failing to parse
+#[tokio::test]
+async fn test_unknown_error_code() {
+    let _mock = mock("GET", "/v3/ticker/")
+        .match_query(Matcher::UrlEncoded("book".into(), "NEWCODEBOOK".into()))
+        .with_status(400)
+        .with_body(
+            r#"{
+            "success": false,
+            "error": {
+                "code": "0299",
+                "message": "Some new authentication error"
+            }
+        }"#,
+        )
+        .create();
+    let bitso = Bitso::default()
+        .prefix(mockito::server_url().as_str())
+        .build();
+    let result = bitso
+        .get_ticker(&Book::Other("NEWCODEBOOK".to_owned()))
+        .await;
+    println!("{:?}", result);
+    let error = result.unwrap_err();
+    let code = error.code().unwrap();
+    assert_eq!(code, &ErrorCode::Other("0299".to_owned()));
+    assert_eq!(code.as_str(), "0299");
+    assert_eq!(code.category(), ErrorCategory::Authentication);
+}
+
+/// Test that a non-JSON error body is kept instead of panicking
+#[tokio::test]
+async fn test_non_json_error_body() {
+    let _mock = mock("GET", "/v3/ticker/")
+        .match_query(Matcher::UrlEncoded("book".into(), "NONJSONBOOK".into()))
+        .with_status(400)
+        .with_body("<html>Bad Request</html>")
+        .create();
+    let bitso = Bitso::default()
+        .prefix(mockito::server_url().as_str())
+        .build();
+    let result = bitso
+        .get_ticker(&Book::Other("NONJSONBOOK".to_owned()))
+        .await;
+    println!("{:?}", result);
+    match result.unwrap_err() {
+        BitsoError::Http { status, body } => {
+            assert_eq!(status.as_u16(), 400);
+            assert_eq!(body, "<html>Bad Request</html>");
+        }
+        e => panic!("Expected BitsoError::Http, got {:?}", e),
+    }
+}
+
+/// Test that an unexpected success body surfaces as a decode error
+#[tokio::test]
+async fn test_decode_error() {
+    let _mock = mock("GET", "/v3/ticker/")
+        .match_query(Matcher::UrlEncoded("book".into(), "BADBODYBOOK".into()))
+        .with_status(200)
+        .with_body(r#"{"success": true}"#)
+        .create();
+    let bitso = Bitso::default()
+        .prefix(mockito::server_url().as_str())
+        .build();
+    let result = bitso
+        .get_ticker(&Book::Other("BADBODYBOOK".to_owned()))
+        .await;
+    println!("{:?}", result);
+    match result.unwrap_err() {
+        BitsoError::Decode { body, .. } => assert_eq!(body, r#"{"success": true}"#),
+        e => panic!("Expected BitsoError::Decode, got {:?}", e),
+    }
 }
 
 /// Test successful request to get available books
@@ -94,9 +188,12 @@ async fn test_ticker() {
     let bitso = Bitso::default()
         .prefix(mockito::server_url().as_str())
         .build();
-    let result = bitso.get_ticker("btc_mxn").await;
+    let result = bitso.get_ticker(&Book::BtcMxn).await;
     assert!(result.is_ok());
     println!("{:?}", result);
+    let ticker = result.unwrap().payload;
+    assert_eq!(ticker.volume, Some(dec!(22.31349615)));
+    assert_eq!(ticker.ask, Some(dec!(5632.24)));
 }
 
 /// Test successful request to get order book
@@ -143,7 +240,7 @@ async fn test_order_book() {
     let bitso = Bitso::default()
         .prefix(mockito::server_url().as_str())
         .build();
-    let result = bitso.get_order_book("btc_mxn", false).await;
+    let result = bitso.get_order_book(&Book::BtcMxn, false).await;
     assert!(result.is_ok());
     println!("{:?}", result);
 }
@@ -192,7 +289,7 @@ async fn test_order_book_with_optional_params() {
     let bitso = Bitso::default()
         .prefix(mockito::server_url().as_str())
         .build();
-    let result = bitso.get_order_book("btc_mxn", true).await;
+    let result = bitso.get_order_book(&Book::BtcMxn, true).await;
     assert!(result.is_ok());
     println!("{:?}", result);
 }
@@ -227,7 +324,7 @@ async fn test_trades() {
     let bitso = Bitso::default()
         .prefix(mockito::server_url().as_str())
         .build();
-    let result = bitso.get_trades("btc_mxn", None).await;
+    let result = bitso.get_trades(&Book::BtcMxn, None).await;
     assert!(result.is_ok());
     println!("{:?}", result);
 }
@@ -266,7 +363,7 @@ async fn test_trades_with_optional_params() {
         sort: Some("asc"),
         limit: Some(&1),
     };
-    let result = bitso.get_trades("btc_mxn", Some(optional_params)).await;
+    let result = bitso.get_trades(&Book::BtcMxn, Some(optional_params)).await;
     assert!(result.is_ok());
     println!("{:?}", result);
 }
@@ -275,10 +372,60 @@ async fn test_trades_with_optional_params() {
 #[tokio::test]
 async fn extern_errors() {
     let bitso = Bitso::default().prefix("bad prefix").build();
-    let result = bitso.get_ticker("eth_mxn").await;
+    let result = bitso.get_ticker(&Book::EthMxn).await;
     println!("{:?}", result);
+    let error = result.unwrap_err();
     assert_eq!(
-        result.unwrap_err().to_string(),
+        error.to_string(),
         "builder error: relative URL without a base"
     );
+    assert!(matches!(error, BitsoError::Transport(_)));
+}
+
+/// Test that each Bitso object sends requests with its own HTTP settings
+#[tokio::test]
+async fn test_http_config() {
+    let _mock = mock("GET", "/v3/ticker/")
+        .match_query(Matcher::UrlEncoded("book".into(), "config_mxn".into()))
+        .match_header("user-agent", "bitsors-test/1.0")
+        .match_header("x-desk", "mx-1")
+        .with_status(200)
+        .with_body(
+            r#"{
+            "success": true,
+            "payload": {
+                "book": "config_mxn",
+                "volume": "22.31349615",
+                "high": "5750.00",
+                "last": "5633.98",
+                "low": "5450.00",
+                "vwap": "5393.45",
+                "ask": "5632.24",
+                "bid": "5520.01",
+                "created_at": "2016-04-08T17:52:31.000+00:00"
+            }
+        }"#,
+        )
+        .create();
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("x-desk", "mx-1".parse().unwrap());
+    let config = HttpConfig::default()
+        .user_agent("bitsors-test/1.0")
+        .default_headers(headers)
+        .timeout(std::time::Duration::from_secs(5))
+        .build();
+    let bitso = Bitso::default()
+        .prefix(mockito::server_url().as_str())
+        .http_config(config)
+        .unwrap()
+        .build();
+    let result = bitso.get_ticker(&Book::from("config_mxn")).await;
+    println!("{:?}", result);
+    assert!(result.is_ok());
+    // A Bitso object with the default client does not send those headers
+    let bitso = Bitso::default()
+        .prefix(mockito::server_url().as_str())
+        .http_client(reqwest::Client::new())
+        .build();
+    assert!(bitso.get_ticker(&Book::from("config_mxn")).await.is_err());
 }
@@ -0,0 +1,107 @@
+extern crate bitsors;
+extern crate mockito;
+
+use bitsors::client::{ApiType, Bitso};
+use bitsors::rate_limit::{Budget, RateLimiter};
+use mockito::mock;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Test that requests beyond the budget wait for it to refill
+#[tokio::test]
+async fn test_acquire_waits_when_budget_is_spent() {
+    let limiter = RateLimiter::unlimited()
+        .public_budget(Some(Budget::new(2, Duration::from_millis(200)).unwrap()))
+        .build();
+    assert_eq!(
+        limiter.acquire(ApiType::Public).await,
+        Duration::from_secs(0)
+    );
+    assert_eq!(
+        limiter.acquire(ApiType::Public).await,
+        Duration::from_secs(0)
+    );
+    let start = Instant::now();
+    let waited = limiter.acquire(ApiType::Public).await;
+    assert!(waited > Duration::from_millis(50));
+    assert!(start.elapsed() >= waited);
+    let stats = limiter.stats(ApiType::Public);
+    assert_eq!(stats.requests, 3);
+    assert_eq!(stats.throttled, 1);
+    assert_eq!(stats.last_wait, waited);
+}
+
+/// Test that public and private budgets are independent
+#[tokio::test]
+async fn test_separate_budgets() {
+    let limiter = RateLimiter::default()
+        .public_budget(Some(Budget::new(1, Duration::from_secs(60)).unwrap()))
+        .private_budget(None)
+        .build();
+    assert_eq!(
+        limiter.acquire(ApiType::Public).await,
+        Duration::from_secs(0)
+    );
+    for _ in 0..10 {
+        assert_eq!(
+            limiter.acquire(ApiType::Private).await,
+            Duration::from_secs(0)
+        );
+    }
+    assert_eq!(limiter.budget(ApiType::Private), None);
+    assert_eq!(
+        limiter.budget(ApiType::Public),
+        Some(Budget::new(1, Duration::from_secs(60)).unwrap())
+    );
+    assert_eq!(limiter.stats(ApiType::Private).requests, 0);
+}
+
+/// Test that clones of a Bitso object share the same limiter
+#[tokio::test]
+async fn test_limiter_shared_across_clones() {
+    let _mock = mock("GET", "/v3/available_books/")
+        .with_status(200)
+        .with_body(r#"{"success": true, "payload": []}"#)
+        .expect(3)
+        .create();
+    let limiter = Arc::new(
+        RateLimiter::unlimited()
+            .public_budget(Some(Budget::new(2, Duration::from_millis(300)).unwrap()))
+            .build(),
+    );
+    let bitso = Bitso::default()
+        .prefix(mockito::server_url().as_str())
+        .rate_limiter(limiter.clone())
+        .build();
+    let other = bitso.clone();
+    let (a, b) = tokio::join!(bitso.get_available_books(), other.get_available_books());
+    assert!(a.is_ok() && b.is_ok());
+    let task = tokio::spawn(async move { other.get_available_books().await.is_ok() });
+    assert!(task.await.unwrap());
+    let stats = limiter.stats(ApiType::Public);
+    assert_eq!(stats.requests, 3);
+    assert_eq!(stats.throttled, 1);
+    assert!(stats.total_wait > Duration::from_millis(50));
+}
+
+/// Test that Bitso objects do not throttle by default and zero periods are rejected
+#[tokio::test]
+async fn test_default_and_zero_period() {
+    let bitso = Bitso::default();
+    assert_eq!(bitso.rate_limiter.budget(ApiType::Public), None);
+    assert_eq!(bitso.rate_limiter.budget(ApiType::Private), None);
+
+    assert!(Budget::new(10, Duration::from_secs(0)).is_err());
+    let limiter = RateLimiter::unlimited()
+        .public_budget(Some(Budget {
+            requests: 1,
+            period: Duration::from_secs(0),
+        }))
+        .build();
+    for _ in 0..3 {
+        assert_eq!(
+            limiter.acquire(ApiType::Public).await,
+            Duration::from_secs(0)
+        );
+    }
+}
@@ -0,0 +1,145 @@
+extern crate bitsors;
+
+use bitsors::hub::{Backpressure, WebSocketHub};
+use bitsors::recording::{FrameKind, RecordedFrame, Replay};
+use bitsors::websocket::*;
+use std::io::Cursor;
+use std::net::TcpListener;
+use std::thread;
+use std::time::{Duration, Instant};
+use tungstenite::{accept, Message};
+
+const TRADE: &str =
+    r#"{"type":"trades","book":"btc_mxn","payload":[{"i":1,"a":"0.1","r":"500000","v":"50000"}]}"#;
+const DIFF: &str = r#"{"type":"diff-orders","book":"btc_mxn","sequence":7,"payload":[]}"#;
+
+/// Test that a live session is recorded frame by frame, control frames
+/// included, and replays into the same responses
+#[test]
+fn test_record_and_replay() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = accept(stream).unwrap();
+        socket.write_message(Message::Ping(vec![1])).unwrap();
+        for frame in &[TRADE, r#"{"type":"ka"}"#, DIFF] {
+            socket
+                .write_message(Message::Text(frame.to_string()))
+                .unwrap();
+        }
+        socket
+            .write_message(Message::Binary(vec![0xff, 0xfe]))
+            .unwrap();
+        socket.close(None).unwrap();
+        socket.write_pending().ok();
+    });
+
+    let path = std::env::temp_dir().join(format!("bitsors-recording-{}.jsonl", std::process::id()));
+    let mut socket = BitsoWebSocket::connect(&url)
+        .unwrap()
+        .record(&path)
+        .unwrap();
+    let live = [socket.read().unwrap(), socket.read().unwrap()];
+    // The binary frame, then the close frame
+    assert!(socket.read().is_err());
+    assert!(socket.read().is_err());
+    server.join().unwrap();
+
+    let recording = std::fs::read_to_string(&path).unwrap();
+    let frames: Vec<RecordedFrame> = recording
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(frames.len(), 6);
+    assert_eq!(frames[0].kind, FrameKind::Ping);
+    assert_eq!(frames[1].kind, FrameKind::Data);
+    assert_eq!(frames[1].text.as_deref(), Some(TRADE));
+    assert_eq!(frames[2].text.as_deref(), Some(r#"{"type":"ka"}"#));
+    assert_eq!(frames[4].binary, Some(vec![0xff, 0xfe]));
+    assert_eq!(frames[5].kind, FrameKind::Close);
+    assert!(!recording.lines().nth(1).unwrap().contains("kind"));
+    assert!(frames
+        .windows(2)
+        .all(|w| w[0].received_at <= w[1].received_at));
+
+    let mut replay = Replay::open(&path).unwrap();
+    assert_eq!(replay.read().unwrap(), live[0]);
+    assert_eq!(replay.read().unwrap(), live[1]);
+    // Same decode error as the live socket, then the end of the recording
+    assert!(matches!(
+        replay.read(),
+        Err(bitsors::error::BitsoError::Decode { .. })
+    ));
+    // The recorded close, then the end of the recording
+    assert!(replay.read().is_err());
+    assert!(replay.read().is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Test that a recorded close is replayed as a disconnect
+#[test]
+fn test_replay_close() {
+    let recording = format!(
+        "{}\n{}\n{}\n{}\n",
+        serde_json::json!({"received_at": 1, "text": TRADE}),
+        serde_json::json!({"received_at": 2, "kind": "ping", "text": ""}),
+        serde_json::json!({"received_at": 3, "kind": "close", "text": "bye"}),
+        serde_json::json!({"received_at": 4, "text": DIFF}),
+    );
+    let mut replay = Replay::new(Cursor::new(recording));
+    assert!(matches!(replay.read().unwrap(), Response::Trades(_)));
+    assert!(matches!(
+        replay.read(),
+        Err(bitsors::error::BitsoError::WebSocket(_))
+    ));
+    assert!(matches!(replay.read().unwrap(), Response::DiffOrders(_)));
+    assert!(replay.read().is_err());
+}
+
+/// Test that real time replays keep the recorded delays
+#[test]
+fn test_replay_real_time() {
+    let recording = format!(
+        "{}\n{}\n",
+        serde_json::json!({"received_at": 1_000, "text": TRADE}),
+        serde_json::json!({"received_at": 1_150, "text": DIFF}),
+    );
+
+    let start = Instant::now();
+    let mut replay = Replay::new(Cursor::new(recording.clone()));
+    replay.read().unwrap();
+    replay.read().unwrap();
+    assert!(start.elapsed() < Duration::from_millis(150));
+
+    let start = Instant::now();
+    let mut replay = Replay::new(Cursor::new(recording)).real_time(true);
+    assert!(matches!(replay.read().unwrap(), Response::Trades(_)));
+    assert!(matches!(replay.read().unwrap(), Response::DiffOrders(_)));
+    assert!(start.elapsed() >= Duration::from_millis(150));
+}
+
+/// Test that a replay can feed a hub like a live connection
+#[test]
+fn test_replay_into_hub() {
+    let recording = format!(
+        "{}\n{}\n",
+        serde_json::json!({"received_at": 1, "text": TRADE}),
+        serde_json::json!({"received_at": 2, "text": DIFF}),
+    );
+    let mut hub = WebSocketHub::new(Replay::new(Cursor::new(recording)));
+    let diffs = hub
+        .subscribe(
+            Subscription::DiffOrders,
+            Book::BtcMxn,
+            8,
+            Backpressure::Block,
+        )
+        .unwrap();
+    assert!(hub.run().join().unwrap().is_err());
+    match diffs.recv() {
+        Some(Response::DiffOrders(r)) => assert_eq!(r.sequence, 7),
+        r => panic!("Did not get a Response::DiffOrders: {:?}", r),
+    }
+    assert!(diffs.recv().is_none());
+}
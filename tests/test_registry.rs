@@ -0,0 +1,144 @@
+extern crate bitsors;
+
+use bitsors::book::Book;
+use bitsors::client::Bitso;
+use bitsors::error::BitsoError;
+use bitsors::registry::BookRegistry;
+use bitsors::transport::MemoryTransport;
+use reqwest::{Method, StatusCode};
+use rust_decimal_macros::dec;
+
+fn available_books(books: &[&str]) -> String {
+    let books = books
+        .iter()
+        .map(|book| {
+            format!(
+                r#"{{"book":"{}","minimum_amount":".003","maximum_amount":"1000.00","minimum_price":"100.00","maximum_price":"1000000.00","minimum_value":"25.00","maximum_value":"1000000.00"}}"#,
+                book
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(r#"{{"success":true,"payload":[{}]}}"#, books)
+}
+
+/// Test that books are loaded with their limits and can be looked up
+#[tokio::test]
+async fn test_registry_lookup() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/available_books/",
+        StatusCode::OK,
+        &available_books(&["btc_mxn", "eth_mxn", "sol_usd"]),
+    );
+    let bitso = Bitso::default().transport(transport.clone()).build();
+    let registry = BookRegistry::load(bitso).await.unwrap();
+    assert_eq!(registry.len(), 3);
+
+    let limits = registry.get(&Book::BtcMxn).unwrap();
+    assert_eq!(limits.minimum_amount, dec!(0.003));
+    assert_eq!(limits.maximum_amount, dec!(1000));
+    assert_eq!(limits.minimum_price, dec!(100));
+    assert_eq!(limits.maximum_price, dec!(1_000_000));
+    assert_eq!(limits.minimum_value, dec!(25));
+    assert_eq!(limits.maximum_value, dec!(1_000_000));
+
+    assert_eq!(registry.by_name("eth_mxn").unwrap().book, Book::EthMxn);
+    assert_eq!(
+        registry.by_pair("SOL", "usd").unwrap().book,
+        Book::new("sol", "usd")
+    );
+    assert!(registry.by_pair("btc", "usd").is_none());
+
+    let mut mxn: Vec<String> = registry
+        .by_quote("mxn")
+        .map(|limits| limits.book.to_string())
+        .collect();
+    mxn.sort_unstable();
+    assert_eq!(mxn, vec!["btc_mxn", "eth_mxn"]);
+
+    assert!(registry.contains(&Book::from("sol_usd")));
+    assert!(registry.validate(&Book::EthMxn).is_ok());
+    assert!(matches!(
+        registry.validate(&Book::XrpMxn),
+        Err(BitsoError::Validation(_))
+    ));
+}
+
+/// Test that a refresh picks up listing changes and keeps the
+/// previous books when it fails
+#[tokio::test]
+async fn test_registry_refresh() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/available_books/",
+        StatusCode::OK,
+        &available_books(&["btc_mxn"]),
+    );
+    transport.respond(
+        Method::GET,
+        "/v3/available_books/",
+        StatusCode::OK,
+        &available_books(&["btc_mxn", "sol_mxn"]),
+    );
+    transport.respond(
+        Method::GET,
+        "/v3/available_books/",
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "oops",
+    );
+    let bitso = Bitso::default().transport(transport.clone()).build();
+    let mut registry = BookRegistry::load(bitso).await.unwrap();
+    assert!(!registry.contains(&Book::new("sol", "mxn")));
+
+    let loaded_at = registry.refreshed_at();
+    registry.refresh().await.unwrap();
+    assert!(registry.contains(&Book::new("sol", "mxn")));
+    assert!(registry.refreshed_at() >= loaded_at);
+
+    assert!(registry.refresh().await.is_err());
+    assert_eq!(registry.len(), 2);
+    assert_eq!(transport.requests().len(), 3);
+}
+
+/// Test that entries with missing limits are skipped and reported while
+/// the rest of the listing is kept, and that an undecodable response is
+/// rejected
+#[tokio::test]
+async fn test_registry_invalid_limits() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/available_books/",
+        StatusCode::OK,
+        &available_books(&["eth_mxn"]).replace(
+            r#"[{"#,
+            r#"[{"book":"btc_mxn","minimum_amount":"0.003"},{"minimum_amount":"1"},{"#,
+        ),
+    );
+    transport.respond(
+        Method::GET,
+        "/v3/available_books/",
+        StatusCode::OK,
+        r#"{"success":true,"payload":[{"book":"btc_mxn","minimum_amount":"lots"}]}"#,
+    );
+    let bitso = Bitso::default().transport(transport).build();
+    let registry = BookRegistry::load(bitso.clone()).await.unwrap();
+    assert_eq!(registry.len(), 1);
+    assert!(registry.contains(&Book::EthMxn));
+    let skipped: Vec<Option<Book>> = registry
+        .skipped()
+        .iter()
+        .map(|(book, _)| book.clone())
+        .collect();
+    assert_eq!(skipped, vec![Some(Book::BtcMxn), None]);
+    assert!(registry
+        .skipped()
+        .iter()
+        .all(|(_, error)| matches!(error, BitsoError::Validation(_))));
+
+    let result = BookRegistry::load(bitso).await;
+    assert!(matches!(result, Err(BitsoError::Decode { .. })));
+}
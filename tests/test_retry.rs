@@ -0,0 +1,301 @@
+extern crate bitsors;
+extern crate mockito;
+
+mod common;
+
+use bitsors::book::Book;
+use bitsors::client::Bitso;
+use bitsors::error::{BitsoError, ErrorCode};
+use bitsors::model::{OrderSide, OrderType};
+use bitsors::retry::RetryPolicy;
+use bitsors::transport::{HttpResponse, MemoryTransport};
+use chrono::{DateTime, Utc};
+use common::credentials;
+use mockito::{mock, Matcher};
+use reqwest::{Method, StatusCode};
+use std::time::Duration;
+
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy::default()
+        .max_attempts(3)
+        .base_delay(Duration::from_millis(1))
+        .jitter(false)
+        .build()
+}
+
+/// Test that a retryable status is retried until the request succeeds
+#[tokio::test]
+async fn test_retry_until_success() {
+    let failing = mock("GET", "/v3/ticker/")
+        .match_query(Matcher::UrlEncoded("book".into(), "retry_mxn".into()))
+        .with_status(503)
+        .with_body("Service Unavailable")
+        .expect(2)
+        .create();
+    let succeeding = mock("GET", "/v3/ticker/")
+        .match_query(Matcher::UrlEncoded("book".into(), "retry_mxn".into()))
+        .with_status(200)
+        .with_body(
+            r#"{
+            "success": true,
+            "payload": {
+                "book": "retry_mxn",
+                "volume": "22.31349615",
+                "high": "5750.00",
+                "last": "5633.98",
+                "low": "5450.00",
+                "vwap": "5393.45",
+                "ask": "5632.24",
+                "bid": "5520.01",
+                "created_at": "2016-04-08T17:52:31.000+00:00"
+            }
+        }"#,
+        )
+        .expect(1)
+        .create();
+    let bitso = Bitso::default()
+        .prefix(mockito::server_url().as_str())
+        .retry_policy(fast_policy())
+        .build();
+    let result = bitso.get_ticker(&Book::from("retry_mxn")).await;
+    println!("{:?}", result);
+    assert!(result.is_ok());
+    failing.assert();
+    succeeding.assert();
+}
+
+/// Test that retries stop after the maximum number of attempts
+#[tokio::test]
+async fn test_retry_gives_up() {
+    let failing = mock("GET", "/v3/ticker/")
+        .match_query(Matcher::UrlEncoded("book".into(), "gives_up_mxn".into()))
+        .with_status(500)
+        .with_body("Internal Server Error")
+        .expect(3)
+        .create();
+    let bitso = Bitso::default()
+        .prefix(mockito::server_url().as_str())
+        .retry_policy(fast_policy())
+        .build();
+    let result = bitso.get_ticker(&Book::from("gives_up_mxn")).await;
+    println!("{:?}", result);
+    match result.unwrap_err() {
+        BitsoError::Http { status, .. } => assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR),
+        e => panic!("Expected BitsoError::Http, got {:?}", e),
+    }
+    failing.assert();
+}
+
+/// Test that non-retryable Bitso error codes are returned right away
+#[tokio::test]
+async fn test_no_retry_on_client_error() {
+    let failing = mock("GET", "/v3/ticker/")
+        .match_query(Matcher::UrlEncoded("book".into(), "FAKEORDERBOOK".into()))
+        .with_status(400)
+        .with_body(
+            r#"{
+            "success": false,
+            "error": {
+                "code": "0301",
+                "message": "Unknown OrderBook FAKEORDERBOOK"
+            }
+        }"#,
+        )
+        .expect(1)
+        .create();
+    let bitso = Bitso::default()
+        .prefix(mockito::server_url().as_str())
+        .retry_policy(fast_policy())
+        .build();
+    let result = bitso
+        .get_ticker(&Book::Other("FAKEORDERBOOK".to_owned()))
+        .await;
+    assert_eq!(
+        result.unwrap_err().code(),
+        Some(&ErrorCode::UnknownOrderBook)
+    );
+    failing.assert();
+}
+
+/// Test that the Retry-After header is respected
+#[tokio::test]
+async fn test_retry_after_header() {
+    let failing = mock("GET", "/v3/available_books/")
+        .with_status(429)
+        .with_header("Retry-After", "1")
+        .with_body("Too Many Requests")
+        .expect(1)
+        .create();
+    let succeeding = mock("GET", "/v3/available_books/")
+        .with_status(200)
+        .with_body(r#"{"success": true, "payload": []}"#)
+        .expect(1)
+        .create();
+    let bitso = Bitso::default()
+        .prefix(mockito::server_url().as_str())
+        .retry_policy(fast_policy())
+        .build();
+    let start = std::time::Instant::now();
+    let result = bitso.get_available_books().await;
+    assert!(result.is_ok());
+    assert!(start.elapsed() >= Duration::from_secs(1));
+    failing.assert();
+    succeeding.assert();
+}
+
+/// Test that a long Retry-After is capped by the maximum delay
+#[tokio::test]
+async fn test_retry_after_is_capped() {
+    let transport = MemoryTransport::new();
+    let mut busy = HttpResponse::new(StatusCode::TOO_MANY_REQUESTS, "Too Many Requests");
+    busy.headers.insert("retry-after", "600".parse().unwrap());
+    transport.respond_with(Method::GET, "/v3/available_books/", busy);
+    transport.respond(
+        Method::GET,
+        "/v3/available_books/",
+        StatusCode::OK,
+        r#"{"success": true, "payload": []}"#,
+    );
+    let policy = RetryPolicy::default()
+        .max_delay(Duration::from_millis(10))
+        .build();
+    let bitso = Bitso::default()
+        .transport(transport.clone())
+        .retry_policy(policy)
+        .build();
+    let start = std::time::Instant::now();
+    assert!(bitso.get_available_books().await.is_ok());
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(transport.requests().len(), 2);
+}
+
+/// Test that a Retry-After HTTP date is waited for, and a past one is not
+#[tokio::test]
+async fn test_retry_after_date() {
+    let busy_until = |at: DateTime<Utc>| {
+        let mut busy = HttpResponse::new(StatusCode::TOO_MANY_REQUESTS, "Too Many Requests");
+        let date = at.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        busy.headers.insert("retry-after", date.parse().unwrap());
+        busy
+    };
+    let transport = MemoryTransport::new();
+    transport.respond_with(
+        Method::GET,
+        "/v3/available_books/",
+        busy_until(Utc::now() + chrono::Duration::seconds(2)),
+    );
+    transport.respond_with(
+        Method::GET,
+        "/v3/available_books/",
+        busy_until(Utc::now() - chrono::Duration::days(1)),
+    );
+    transport.respond(
+        Method::GET,
+        "/v3/available_books/",
+        StatusCode::OK,
+        r#"{"success": true, "payload": []}"#,
+    );
+    let bitso = Bitso::default()
+        .transport(transport.clone())
+        .retry_policy(RetryPolicy::default().max_attempts(3).build())
+        .build();
+    let start = std::time::Instant::now();
+    assert!(bitso.get_available_books().await.is_ok());
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_secs(1));
+    assert!(elapsed < Duration::from_secs(3));
+    assert_eq!(transport.requests().len(), 3);
+}
+
+/// Test that POST requests are not retried unless explicitly allowed
+#[tokio::test]
+async fn test_no_retry_on_post() {
+    let failing = mock("POST", "/v3/crypto_withdrawal/")
+        .with_status(503)
+        .with_body("Service Unavailable")
+        .expect(1)
+        .create();
+    let bitso = Bitso::default()
+        .prefix(mockito::server_url().as_str())
+        .client_credentials_manager(credentials())
+        .retry_policy(fast_policy())
+        .build();
+    let result = bitso
+        .crypto_withdrawal(
+            "btc",
+            "0.001",
+            "3EW92Ajg6sMT4hxK8ngEc7Ehrqkr9RoDt7",
+            None,
+            None,
+        )
+        .await;
+    assert!(result.is_err());
+    failing.assert();
+}
+
+/// Test that POST requests are retried when opted in
+#[tokio::test]
+async fn test_retry_on_post_opt_in() {
+    let failing = mock("POST", "/v3/orders/")
+        .with_status(503)
+        .with_body("Service Unavailable")
+        .expect(1)
+        .create();
+    let succeeding = mock("POST", "/v3/orders/")
+        .with_status(200)
+        .with_body(
+            r#"{
+            "success": true,
+            "payload": {
+                "oid": "qlbga6b600n3xta7"
+            }
+        }"#,
+        )
+        .expect(1)
+        .create();
+    let bitso = Bitso::default()
+        .prefix(mockito::server_url().as_str())
+        .client_credentials_manager(credentials())
+        .retry_policy(fast_policy().retry_non_idempotent(true))
+        .build();
+    let result = bitso
+        .place_order(&Book::BtcMxn, OrderSide::Sell, OrderType::Market, None)
+        .await;
+    assert!(result.is_ok());
+    failing.assert();
+    succeeding.assert();
+}
+
+/// Test which requests the policy considers retryable
+#[test]
+fn test_should_retry() {
+    let policy = fast_policy();
+    let unavailable = BitsoError::Http {
+        status: StatusCode::SERVICE_UNAVAILABLE,
+        body: String::new(),
+    };
+    assert!(policy.should_retry(&Method::GET, &unavailable, 1));
+    assert!(policy.should_retry(&Method::DELETE, &unavailable, 2));
+    assert!(!policy.should_retry(&Method::GET, &unavailable, 3));
+    assert!(!policy.should_retry(&Method::POST, &unavailable, 1));
+    assert!(!policy.should_retry(&Method::GET, &BitsoError::MissingCredentials, 1));
+    assert!(!RetryPolicy::none().should_retry(&Method::GET, &unavailable, 1));
+}
+
+/// Test that the backoff grows exponentially and is capped
+#[test]
+fn test_backoff() {
+    let policy = RetryPolicy::default()
+        .base_delay(Duration::from_millis(100))
+        .max_delay(Duration::from_millis(350))
+        .jitter(false)
+        .build();
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(policy.backoff(3), Duration::from_millis(350));
+    let jittered = policy.jitter(true).build();
+    for attempt in 1..5 {
+        let delay = jittered.backoff(attempt);
+        assert!(delay <= Duration::from_millis(350));
+    }
+}
@@ -0,0 +1,98 @@
+extern crate bitsors;
+
+use bitsors::model::private::UserTradesPayload;
+use bitsors::model::public::Trade;
+use bitsors::model::websocket::DiffOrders;
+use bitsors::model::{sort_by_time, DateTime, Timestamped, Utc};
+
+fn utc(time: &str) -> DateTime<Utc> {
+    time.parse().unwrap()
+}
+
+fn trade(tid: u64, created_at: Option<&str>) -> Trade {
+    serde_json::from_value(serde_json::json!({
+        "book": "btc_mxn",
+        "created_at": created_at,
+        "amount": "0.1",
+        "maker_side": "buy",
+        "price": "500000",
+        "tid": tid
+    }))
+    .unwrap()
+}
+
+/// Test that every offset format sent by Bitso is converted to UTC
+#[test]
+fn test_parse_offsets() {
+    let expected = utc("2016-04-08T17:52:31Z");
+    for created_at in &[
+        "2016-04-08T17:52:31.000+00:00",
+        "2016-04-08T17:52:31+0000",
+        "2016-04-08T11:52:31.000-06:00",
+        "2016-04-08T11:52:31-0600",
+    ] {
+        assert_eq!(
+            trade(1, Some(created_at)).created_at,
+            Some(expected),
+            "{}",
+            created_at
+        );
+    }
+
+    let user_trade: UserTradesPayload = serde_json::from_value(serde_json::json!({
+        "book": "btc_mxn",
+        "created_at": "2016-04-08T17:52:31.000+00:00",
+        "tid": 1
+    }))
+    .unwrap();
+    assert_eq!(user_trade.timestamp(), Some(expected));
+
+    let invalid = serde_json::from_value::<Trade>(serde_json::json!({
+        "created_at": "yesterday"
+    }));
+    assert!(invalid.is_err());
+}
+
+/// Test that WebSocket timestamps are read as Unix milliseconds
+#[test]
+fn test_parse_websocket_millis() {
+    let diff: DiffOrders = serde_json::from_str(
+        r#"{"type":"diff-orders","book":"btc_mxn","sequence":1,"payload":[{"d":1460137951123,"r":"100","t":0,"o":"a","s":"open","a":"1","v":"100"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(diff.payload[0].d, utc("2016-04-08T17:52:31.123Z"));
+    assert_eq!(diff.payload[0].timestamp(), Some(diff.payload[0].d));
+
+    // Serialized back to the same representation
+    let json = serde_json::to_value(&diff).unwrap();
+    assert_eq!(json["payload"][0]["d"], 1460137951123_i64);
+}
+
+/// Test filtering and sorting by time
+#[test]
+fn test_order_by_time() {
+    let mut trades = vec![
+        trade(3, Some("2021-01-03T00:00:00+00:00")),
+        trade(1, Some("2021-01-01T00:00:00+00:00")),
+        trade(4, None),
+        trade(2, Some("2021-01-02T00:00:00-06:00")),
+    ];
+    let start = utc("2021-01-02T00:00:00Z");
+    let end = utc("2021-01-03T00:00:00Z");
+    assert!(trades[1].is_before(start));
+    assert!(trades[0].is_after(end));
+    assert!(!trades[2].is_after(start) && !trades[2].is_before(start));
+    let between: Vec<u64> = trades
+        .iter()
+        .filter(|t| t.is_between(start, end))
+        .filter_map(|t| t.tid.map(|tid| tid.0))
+        .collect();
+    assert_eq!(between, vec![2]);
+
+    sort_by_time(&mut trades);
+    let tids: Vec<u64> = trades
+        .iter()
+        .filter_map(|t| t.tid.map(|tid| tid.0))
+        .collect();
+    assert_eq!(tids, vec![4, 1, 2, 3]);
+}
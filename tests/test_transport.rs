@@ -0,0 +1,259 @@
+extern crate bitsors;
+
+mod common;
+
+use bitsors::book::Book;
+use bitsors::client::{CancelTarget, OptionalOrderParams};
+use bitsors::model::{OrderId, OrderSide, OrderType, OriginId, TimeInForce, TradeId};
+use bitsors::retry::RetryPolicy;
+use bitsors::transport::{HttpResponse, MemoryTransport};
+use common::bitso;
+use reqwest::header::AUTHORIZATION;
+use reqwest::{Method, StatusCode};
+use rust_decimal_macros::dec;
+use std::time::Duration;
+
+fn nonce(authorization: &str) -> String {
+    authorization.split(':').nth(1).unwrap().to_owned()
+}
+
+/// Test that public requests are recorded with their query parameters
+#[tokio::test]
+async fn test_public_request_is_recorded() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/order_book/",
+        StatusCode::OK,
+        r#"{
+            "success": true,
+            "payload": {
+                "asks": [],
+                "bids": [],
+                "updated_at": "2016-04-08T17:52:31.000+00:00",
+                "sequence": "27214"
+            }
+        }"#,
+    );
+    let result = bitso(&transport).get_order_book(&Book::BtcMxn, true).await;
+    assert!(result.is_ok());
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, Method::GET);
+    assert_eq!(requests[0].path(), "/v3/order_book/");
+    assert_eq!(requests[0].query_param("book").as_deref(), Some("btc_mxn"));
+    assert_eq!(
+        requests[0].query_param("aggregate").as_deref(),
+        Some("true")
+    );
+    assert!(requests[0].headers.get(AUTHORIZATION).is_none());
+}
+
+/// Test that private requests are signed and carry their JSON body
+#[tokio::test]
+async fn test_private_request_is_signed() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::POST,
+        "/v3/orders/",
+        StatusCode::OK,
+        r#"{"success": true, "payload": {"oid": "qlbga6b600n3xta7"}}"#,
+    );
+    let optional_order_params = OptionalOrderParams {
+        major: Some(dec!(0.0001)),
+        minor: None,
+        price: None,
+        stop: None,
+        time_in_force: Some(TimeInForce::FillOrKill),
+        origin_id: None,
+    };
+    let result = bitso(&transport)
+        .place_order(
+            &Book::BtcMxn,
+            OrderSide::Sell,
+            OrderType::Market,
+            Some(optional_order_params),
+        )
+        .await;
+    assert_eq!(
+        result.unwrap().payload.oid,
+        Some(OrderId::from("qlbga6b600n3xta7"))
+    );
+    let request = &transport.requests()[0];
+    let authorization = request.headers[AUTHORIZATION].to_str().unwrap();
+    assert!(authorization.starts_with("Bitso KEY:"));
+    let body = request.body.as_ref().unwrap();
+    assert_eq!(body["book"], "btc_mxn");
+    assert_eq!(body["side"], "sell");
+    assert_eq!(body["type"], "market");
+    assert_eq!(body["major"], "0.0001");
+    assert_eq!(body["time_in_force"], "fillorkill");
+}
+
+/// Test that routes without a canned response get a 404
+#[tokio::test]
+async fn test_unknown_route() {
+    let transport = MemoryTransport::new();
+    let result = bitso(&transport).get_ticker(&Book::BtcMxn).await;
+    match result.unwrap_err() {
+        bitsors::error::BitsoError::Http { status, .. } => {
+            assert_eq!(status, StatusCode::NOT_FOUND)
+        }
+        e => panic!("Expected BitsoError::Http, got {:?}", e),
+    }
+}
+
+/// Test that canned responses are returned in order and
+/// that each retry is signed again with a fresh nonce
+#[tokio::test]
+async fn test_retries_are_signed_again() {
+    let transport = MemoryTransport::new();
+    let mut busy = HttpResponse::new(StatusCode::TOO_MANY_REQUESTS, "Too Many Requests");
+    busy.headers.insert("retry-after", "0".parse().unwrap());
+    transport.respond_with(Method::GET, "/v3/balance/", busy);
+    transport.respond(
+        Method::GET,
+        "/v3/balance/",
+        StatusCode::OK,
+        r#"{"success": true, "payload": {"balances": []}}"#,
+    );
+    let bitso = bitso(&transport)
+        .retry_policy(
+            RetryPolicy::default()
+                .base_delay(Duration::from_millis(1))
+                .build(),
+        )
+        .build();
+    assert!(bitso.get_account_balance().await.is_ok());
+    // The last response keeps being returned
+    assert!(bitso.get_account_balance().await.is_ok());
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 3);
+    let first = requests[0].headers[AUTHORIZATION].to_str().unwrap();
+    let second = requests[1].headers[AUTHORIZATION].to_str().unwrap();
+    assert_ne!(nonce(first), nonce(second));
+    transport.clear_requests();
+    assert!(transport.requests().is_empty());
+}
+
+/// Test that typed IDs are formatted into paths and query parameters
+#[tokio::test]
+async fn test_ids_in_requests() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::GET,
+        "/v3/user_trades/1-2/",
+        StatusCode::OK,
+        r#"{"success": true, "payload": [{"tid": 1, "oid": "a"}, {"tid": 2, "oid": "b"}]}"#,
+    );
+    transport.respond(
+        Method::GET,
+        "/v3/orders/",
+        StatusCode::OK,
+        r#"{"success": true, "payload": []}"#,
+    );
+    let trades = bitso(&transport)
+        .get_user_trades(&Book::BtcMxn, None, Some(&[TradeId(1), TradeId(2)]), None)
+        .await
+        .unwrap()
+        .payload;
+    assert_eq!(trades[1].tid, Some(TradeId(2)));
+    assert_eq!(trades[1].oid, Some(OrderId::from("b")));
+
+    bitso(&transport)
+        .get_lookup_orders(None, Some(&[OrderId::from("a"), OrderId::from("b")]), None)
+        .await
+        .unwrap();
+    let requests = transport.requests();
+    assert_eq!(requests[1].query_param("oids").as_deref(), Some("a,b"));
+}
+
+/// Test that bulk cancels send their IDs and report the orders not cancelled
+#[tokio::test]
+async fn test_cancel_orders() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::DELETE,
+        "/v3/orders/",
+        StatusCode::OK,
+        r#"{"success": true, "payload": ["a", "c"]}"#,
+    );
+    let bitso = bitso(&transport);
+    let oids = [OrderId::from("a"), OrderId::from("b"), OrderId::from("c")];
+    let result = bitso
+        .cancel_order(CancelTarget::Oids(&oids))
+        .await
+        .unwrap()
+        .payload;
+    assert_eq!(
+        result.cancelled,
+        vec![OrderId::from("a"), OrderId::from("c")]
+    );
+    assert_eq!(result.not_found, Some(vec![OrderId::from("b")]));
+
+    let origin_ids = [OriginId::from("mine-1"), OriginId::from("mine-2")];
+    let result = bitso
+        .cancel_order(CancelTarget::OriginIds(&origin_ids))
+        .await
+        .unwrap()
+        .payload;
+    assert_eq!(result.cancelled.len(), 2);
+    assert_eq!(result.not_found, None);
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].method, Method::DELETE);
+    assert_eq!(requests[0].path(), "/v3/orders/");
+    assert_eq!(requests[0].query_param("oids").as_deref(), Some("a,b,c"));
+    assert_eq!(
+        requests[1].query_param("origin_ids").as_deref(),
+        Some("mine-1,mine-2")
+    );
+    assert!(requests[1].query_param("oids").is_none());
+}
+
+/// Test cancelling a single order or every order, and that targets without
+/// IDs or with an empty ID are rejected
+#[tokio::test]
+async fn test_cancel_single_and_all() {
+    let transport = MemoryTransport::new();
+    transport.respond(
+        Method::DELETE,
+        "/v3/orders/a/",
+        StatusCode::OK,
+        r#"{"success": true, "payload": []}"#,
+    );
+    transport.respond(
+        Method::DELETE,
+        "/v3/orders/all",
+        StatusCode::OK,
+        r#"{"success": true, "payload": ["a", "b"]}"#,
+    );
+    let bitso = bitso(&transport);
+    let oid = OrderId::from("a");
+    let result = bitso.cancel_order(CancelTarget::Oid(&oid)).await.unwrap();
+    assert!(result.payload.cancelled.is_empty());
+    assert_eq!(result.payload.not_found, Some(vec![oid]));
+
+    let result = bitso.cancel_order(CancelTarget::All).await.unwrap();
+    assert_eq!(result.payload.cancelled.len(), 2);
+    assert_eq!(result.payload.not_found, Some(vec![]));
+
+    let empty = OrderId::from("");
+    let with_empty = [OrderId::from("b"), OrderId::from("")];
+    let empty_origin = [OriginId::from("")];
+    let invalid = [
+        CancelTarget::Oid(&empty),
+        CancelTarget::Oids(&[]),
+        CancelTarget::Oids(&with_empty),
+        CancelTarget::OriginIds(&[]),
+        CancelTarget::OriginIds(&empty_origin),
+    ];
+    for target in invalid.iter() {
+        match bitso.cancel_order(*target).await {
+            Err(bitsors::error::BitsoError::Validation(_)) => {}
+            e => panic!("Expected BitsoError::Validation, got {:?}", e),
+        }
+    }
+    assert_eq!(transport.requests().len(), 2);
+}
@@ -1,20 +1,29 @@
+use bitsors::error::BitsoError;
 use bitsors::websocket::*;
+use reqwest::header::{HeaderMap, HeaderValue};
+use rust_decimal_macros::dec;
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tungstenite::handshake::server::{Request, Response as HttpResponse};
+use tungstenite::{accept, accept_hdr, Message};
 
 #[cfg(test)]
 #[derive(serde_derive::Deserialize)]
 pub struct BitsoBooks {
-    pub payload: Vec<Book>,
+    pub payload: Vec<BitsoBook>,
 }
 
 #[cfg(test)]
 #[derive(serde_derive::Deserialize)]
-pub struct Book {
+pub struct BitsoBook {
     #[serde(rename = "book")]
     pub name: String,
 }
 
-/// Test that enum Books has the same number of
-/// variants as the current available books in Bitso.
+/// Test that `Book::KNOWN` has the same number of
+/// books as the current available books in Bitso.
 /// Also check that the names match.
 #[tokio::test]
 async fn all_books_and_proper_name() {
@@ -25,7 +34,7 @@ async fn all_books_and_proper_name() {
         .await
         .unwrap();
 
-    assert_eq!(current_books.payload.len(), Books::COUNT);
+    assert_eq!(current_books.payload.len(), Book::KNOWN.len());
 
     let mut current_books: Vec<&str> = current_books
         .payload
@@ -34,7 +43,7 @@ async fn all_books_and_proper_name() {
         .collect();
     current_books.sort_unstable();
 
-    let mut enum_books: Vec<String> = Books::iter().map(|b| b.to_string()).collect();
+    let mut enum_books: Vec<String> = Book::iter().map(|b| b.to_string()).collect();
     enum_books.sort_unstable();
 
     assert_eq!(current_books, enum_books);
@@ -61,7 +70,7 @@ async fn test_websocket_close() {
 async fn test_websocket_subscribe() {
     let mut socket = BitsoWebSocket::new().unwrap();
     socket
-        .subscribe(Subscription::Orders, Books::BtcMxn)
+        .subscribe(Subscription::Orders, Book::BtcMxn)
         .unwrap();
     println!("{:?}", socket);
 }
@@ -71,7 +80,7 @@ async fn test_websocket_subscribe() {
 async fn test_websocket_read() {
     let mut socket = BitsoWebSocket::new().unwrap();
     socket
-        .subscribe(Subscription::Orders, Books::BtcMxn)
+        .subscribe(Subscription::Orders, Book::BtcMxn)
         .unwrap();
     match socket.read().unwrap() {
         Response::Orders(r) => {
@@ -82,3 +91,395 @@ async fn test_websocket_read() {
         _ => panic!("Did not get a Response::Order"),
     }
 }
+
+const TRADE: &str =
+    r#"{"type":"trades","book":"btc_mxn","payload":[{"i":1,"a":"0.1","r":"500000","v":"50000"}]}"#;
+
+/// Test that a dropped connection is dialed again and that
+/// every subscription is sent again on the new connection.
+#[test]
+fn test_websocket_reconnect() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        // First connection: acknowledge the subscription, send a trade and drop.
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = accept(stream).unwrap();
+        let request = socket.read_message().unwrap().into_text().unwrap();
+        assert!(request.contains(r#""type":"trades""#));
+        socket
+            .write_message(Message::Text(
+                r#"{"action":"subscribe","response":"ok","time":1,"type":"trades"}"#.to_owned(),
+            ))
+            .unwrap();
+        socket
+            .write_message(Message::Text(TRADE.to_owned()))
+            .unwrap();
+        drop(socket);
+        // Second connection: expect the same subscription again.
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = accept(stream).unwrap();
+        let request = socket.read_message().unwrap().into_text().unwrap();
+        assert!(request.contains(r#""type":"trades""#));
+        assert!(request.contains(r#""book":"btc_mxn""#));
+        socket
+            .write_message(Message::Text(TRADE.to_owned()))
+            .unwrap();
+        socket.close(None).unwrap();
+        socket.write_pending().ok();
+    });
+
+    let policy = ReconnectPolicy::default()
+        .max_attempts(Some(3))
+        .base_delay(Duration::from_millis(10))
+        .build();
+    let mut socket = BitsoWebSocket::connect(&url)
+        .unwrap()
+        .reconnect_policy(policy);
+    socket
+        .subscribe(Subscription::Trades, Book::BtcMxn)
+        .unwrap();
+    assert!(matches!(socket.read().unwrap(), Response::Trades(_)));
+    assert_eq!(
+        socket.read().unwrap(),
+        Response::Reconnected { attempts: 1 }
+    );
+    assert!(matches!(socket.read().unwrap(), Response::Trades(_)));
+    server.join().unwrap();
+}
+
+/// Test that reads fail without a reconnect policy
+#[test]
+fn test_websocket_no_reconnect() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let socket = accept(stream).unwrap();
+        drop(socket);
+    });
+    let mut socket = BitsoWebSocket::connect(&url).unwrap();
+    server.join().unwrap();
+    assert!(socket.read().is_err());
+}
+
+/// Test that keep alive messages are skipped
+#[test]
+fn test_parse_skipped_messages() {
+    assert_eq!(parse_message(br#"{"type":"ka"}"#).unwrap(), None);
+}
+
+/// Test that acknowledgements are parsed into a SubscriptionAck
+#[test]
+fn test_parse_ack() {
+    let data = br#"{"action":"subscribe","response":"ok","time":1,"type":"trades"}"#;
+    match parse_message(data).unwrap() {
+        Some(Response::Ack(ack)) => {
+            assert!(ack.is_ok());
+            assert_eq!(ack.action, "subscribe");
+            assert_eq!(ack.type_field, "trades");
+            assert_eq!(ack.time, Some(1));
+        }
+        r => panic!("Did not get a Response::Ack: {:?}", r),
+    }
+    let data = br#"{"action":"subscribe","response":"failed","type":"orders"}"#;
+    match parse_message(data).unwrap() {
+        Some(Response::Ack(ack)) => assert!(!ack.is_ok()),
+        r => panic!("Did not get a Response::Ack: {:?}", r),
+    }
+}
+
+/// Test that subscribe waits for its acknowledgement, keeps earlier
+/// messages for read, and tracks the active subscriptions
+#[test]
+fn test_websocket_subscriptions() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = accept(stream).unwrap();
+        let mut send = |text: &str| {
+            socket
+                .write_message(Message::Text(text.to_owned()))
+                .unwrap()
+        };
+        send(r#"{"action":"subscribe","response":"ok","time":1,"type":"trades"}"#);
+        send(TRADE);
+        send(r#"{"action":"subscribe","response":"ok","time":2,"type":"diff-orders"}"#);
+        send(r#"{"action":"subscribe","response":"failed","time":3,"type":"orders"}"#);
+        let mut requests = Vec::new();
+        while requests.len() < 4 {
+            if let Message::Text(text) = socket.read_message().unwrap() {
+                requests.push(text);
+            }
+        }
+        assert!(requests[3].contains(r#""action":"unsubscribe""#));
+        assert!(requests[3].contains(r#""type":"trades""#));
+        socket
+            .write_message(Message::Text(
+                r#"{"action":"unsubscribe","response":"ok","time":4,"type":"trades"}"#.to_owned(),
+            ))
+            .unwrap();
+        socket.close(None).unwrap();
+        socket.write_pending().ok();
+    });
+
+    let mut socket = BitsoWebSocket::connect(&url).unwrap();
+    let ack = socket
+        .subscribe(Subscription::Trades, Book::BtcMxn)
+        .unwrap();
+    assert!(ack.is_ok());
+    assert_eq!(ack.book, Some(Book::BtcMxn));
+    let ack = socket
+        .subscribe(Subscription::DiffOrders, Book::BtcMxn)
+        .unwrap();
+    assert_eq!(ack.type_field, "diff-orders");
+    let ack = socket
+        .subscribe(Subscription::Orders, Book::BtcMxn)
+        .unwrap();
+    assert!(!ack.is_ok());
+    assert_eq!(
+        socket.subscriptions(),
+        &[
+            (Subscription::Trades, Book::BtcMxn),
+            (Subscription::DiffOrders, Book::BtcMxn)
+        ]
+    );
+
+    socket
+        .unsubscribe(Subscription::Trades, Book::BtcMxn)
+        .unwrap();
+    assert_eq!(
+        socket.subscriptions(),
+        &[(Subscription::DiffOrders, Book::BtcMxn)]
+    );
+    // The trade that arrived before the diff-orders acknowledgement
+    assert!(matches!(socket.read().unwrap(), Response::Trades(_)));
+    match socket.read().unwrap() {
+        Response::Ack(ack) => assert_eq!(ack.action, "unsubscribe"),
+        r => panic!("Did not get a Response::Ack: {:?}", r),
+    }
+    server.join().unwrap();
+}
+
+/// Test that subscribe gives up when Bitso never acknowledges, keeping
+/// channel messages but not keep-alives
+#[test]
+fn test_websocket_ack_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let (done, wait) = mpsc::channel::<()>();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = accept(stream).unwrap();
+        for text in &[r#"{"type":"ka"}"#, TRADE, r#"{"type":"ka"}"#] {
+            socket
+                .write_message(Message::Text((*text).to_owned()))
+                .unwrap();
+        }
+        // Never acknowledge the subscription
+        wait.recv().unwrap();
+    });
+
+    let timeout = Duration::from_millis(200);
+    let mut socket = BitsoWebSocket::connect(&url).unwrap().ack_timeout(timeout);
+    let start = Instant::now();
+    match socket.subscribe(Subscription::Trades, Book::BtcMxn) {
+        Err(BitsoError::AckTimeout { timeout: t }) => assert_eq!(t, timeout),
+        r => panic!("Expected an ack timeout, got {:?}", r),
+    }
+    assert!(start.elapsed() >= timeout);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(socket.subscriptions().is_empty());
+    assert!(socket.last_keep_alive().is_some());
+    // Only the trade was kept
+    assert!(matches!(socket.read().unwrap(), Response::Trades(_)));
+
+    done.send(()).unwrap();
+    server.join().unwrap();
+}
+
+/// Test that channel fields can come in any order
+#[test]
+fn test_parse_reordered_fields() {
+    let data = br#"{"sequence":5,"payload":[],"book":"btc_mxn","type":"diff-orders"}"#;
+    match parse_message(data).unwrap() {
+        Some(Response::DiffOrders(r)) => assert_eq!(r.sequence, 5),
+        r => panic!("Did not get a Response::DiffOrders: {:?}", r),
+    }
+}
+
+/// Test that prices and amounts keep every decimal, whether
+/// they are sent as strings or numbers
+#[test]
+fn test_parse_exact_decimals() {
+    let data = br#"{"type":"orders","book":"btc_mxn","payload":{"bids":[{"r":7185.01,"a":0.00000001,"v":0.00007185,"t":0,"d":1}],"asks":[]}}"#;
+    match parse_message(data).unwrap() {
+        Some(Response::Orders(r)) => {
+            let bid = &r.payload.bids[0];
+            assert_eq!(bid.r, dec!(7185.01));
+            assert_eq!(bid.a, dec!(0.00000001));
+            assert_eq!(bid.v, dec!(0.00007185));
+        }
+        r => panic!("Did not get a Response::Orders: {:?}", r),
+    }
+    let data = br#"{"type":"trades","book":"btc_mxn","payload":[{"i":1,"a":"0.00000001","r":"900000.10","v":"0.01"}]}"#;
+    match parse_message(data).unwrap() {
+        Some(Response::Trades(r)) => {
+            assert_eq!(r.payload[0].a, dec!(0.00000001));
+            assert_eq!(r.payload[0].a * r.payload[0].r, dec!(0.0090000010));
+        }
+        r => panic!("Did not get a Response::Trades: {:?}", r),
+    }
+}
+
+/// Test that unknown message types are returned instead of panicking
+#[test]
+fn test_parse_unknown_message() {
+    let data = br#"{"type":"new-channel","book":"btc_mxn","payload":{}}"#;
+    match parse_message(data).unwrap() {
+        Some(Response::Unknown(v)) => assert_eq!(v["type"], "new-channel"),
+        r => panic!("Did not get a Response::Unknown: {:?}", r),
+    }
+    match parse_message(br#"{"error":"boom"}"#).unwrap() {
+        Some(Response::Unknown(v)) => assert_eq!(v["error"], "boom"),
+        r => panic!("Did not get a Response::Unknown: {:?}", r),
+    }
+}
+
+/// Test that malformed frames are returned as errors instead of panicking
+#[test]
+fn test_parse_malformed_messages() {
+    for data in [
+        &b"not json"[..],
+        &[0xff, 0xfe, 0x00][..],
+        &br#"{"type":"trades","book":"btc_mxn"}"#[..],
+        &br#"{"type":"orders","book":"btc_mxn","payload":"oops"}"#[..],
+    ]
+    .iter()
+    {
+        match parse_message(data) {
+            Err(BitsoError::Decode { .. }) => {}
+            r => panic!("Expected a decode error, got {:?}", r),
+        }
+    }
+}
+
+/// Test that binary frames go through the same parsing path
+#[test]
+fn test_websocket_binary_frames() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = accept(stream).unwrap();
+        socket
+            .write_message(Message::Binary(TRADE.as_bytes().to_vec()))
+            .unwrap();
+        socket
+            .write_message(Message::Binary(vec![0xff, 0xfe]))
+            .unwrap();
+        socket.close(None).unwrap();
+        socket.write_pending().ok();
+    });
+    let mut socket = BitsoWebSocket::connect(&url).unwrap();
+    assert!(matches!(socket.read().unwrap(), Response::Trades(_)));
+    assert!(socket.read().is_err());
+    server.join().unwrap();
+}
+
+/// Test that keep-alives are tracked and that a silent
+/// connection is reported as stale once per timeout
+#[test]
+fn test_websocket_stale() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let (done, wait) = mpsc::channel::<()>();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = accept(stream).unwrap();
+        socket
+            .write_message(Message::Text(r#"{"type":"ka"}"#.to_owned()))
+            .unwrap();
+        socket
+            .write_message(Message::Text(TRADE.to_owned()))
+            .unwrap();
+        // Stay silent until the client is done
+        wait.recv().unwrap();
+    });
+
+    let timeout = Duration::from_millis(100);
+    let mut socket = BitsoWebSocket::connect(&url)
+        .unwrap()
+        .stale_timeout(timeout);
+    assert!(matches!(socket.read().unwrap(), Response::Trades(_)));
+    assert!(socket.last_keep_alive().is_some());
+    assert!(socket.last_message().unwrap() >= socket.last_keep_alive().unwrap());
+
+    let start = Instant::now();
+    match socket.read() {
+        Err(BitsoError::Stale { since }) => assert!(since >= timeout),
+        r => panic!("Expected a stale error, got {:?}", r),
+    }
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    let start = Instant::now();
+    assert!(matches!(socket.read(), Err(BitsoError::Stale { .. })));
+    assert!(start.elapsed() >= timeout);
+    assert!(socket.idle() >= 2 * timeout);
+
+    done.send(()).unwrap();
+    server.join().unwrap();
+}
+
+/// Test that the connection honors the configured URL, headers and frame size
+#[test]
+fn test_websocket_config() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        #[allow(clippy::result_large_err)]
+        let check_headers = |request: &Request, response: HttpResponse| {
+            assert_eq!(request.headers()["x-client"], "bitsors-test");
+            Ok(response)
+        };
+        let mut socket = accept_hdr(stream, check_headers).unwrap();
+        socket
+            .write_message(Message::Text(TRADE.to_owned()))
+            .unwrap();
+        socket
+            .write_message(Message::Text("x".repeat(1024)))
+            .unwrap();
+        socket.write_pending().ok();
+        // Keep the socket open until the client has read both frames
+        socket.read_message().ok();
+    });
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-client", HeaderValue::from_static("bitsors-test"));
+    let config = WebSocketConfig::default()
+        .url(&url)
+        .headers(headers)
+        .connect_timeout(Duration::from_secs(5))
+        .max_frame_size(Some(512))
+        .build();
+    let mut socket = BitsoWebSocket::with_config(config).unwrap();
+    assert!(matches!(socket.read().unwrap(), Response::Trades(_)));
+    assert!(matches!(socket.read(), Err(BitsoError::WebSocket(_))));
+    drop(socket);
+    server.join().unwrap();
+}
+
+/// Test that a server that never answers the handshake hits the connect timeout
+#[test]
+fn test_websocket_connect_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let config = WebSocketConfig::default()
+        .url(&format!("ws://{}", listener.local_addr().unwrap()))
+        .connect_timeout(Duration::from_millis(200))
+        .build();
+    let start = Instant::now();
+    assert!(BitsoWebSocket::with_config(config).is_err());
+    assert!(start.elapsed() < Duration::from_secs(5));
+    drop(listener);
+}